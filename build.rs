@@ -0,0 +1,86 @@
+//! Finds every `src/dayNN.rs` and generates the module declarations and the
+//! list of registered days, so adding a day only needs the new file.
+//!
+//! Each day also gets a version: a hash of its source and of the library
+//! sources its solvers use, so cached results can tell when they are stale.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[path = "src/fnv.rs"]
+mod fnv;
+
+/// The library sources, relative to `src/`, that the days' solvers depend
+/// on. The runner, reports and the like can change without invalidating any
+/// cached answers.
+const SOLVER_SOURCES: &[&str] = &["error.rs", "input.rs", "intcode", "problem.rs", "timing.rs"];
+
+fn main() {
+  let src = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("src");
+  println!("cargo:rerun-if-changed={}", src.display());
+
+  let mut days: Vec<String> = fs::read_dir(&src)
+    .expect("Could not read src/")
+    .filter_map(|entry| {
+      let name = entry.ok()?.file_name().into_string().ok()?;
+      let stem = name.strip_suffix(".rs")?;
+      let num = stem.strip_prefix("day")?;
+      if num.len() == 2 && num.chars().all(|c| c.is_ascii_digit()) {
+        Some(stem.to_string())
+      } else {
+        None
+      }
+    })
+    .collect();
+  days.sort();
+
+  let mut shared = Vec::new();
+  for name in SOLVER_SOURCES {
+    let path = src.join(name);
+    if path.is_dir() {
+      find_sources(&path, &mut shared);
+    } else {
+      shared.push(path);
+    }
+  }
+  shared.sort();
+  let shared_hash = shared.iter().fold(fnv::OFFSET, |hash, path| {
+    fnv::hash(hash, &fs::read(path).expect("Could not read a source file"))
+  });
+
+  let mut out = String::new();
+  for day in &days {
+    let path = src.join(format!("{}.rs", day));
+    out.push_str(&format!(
+      "#[path = {:?}]\npub mod {};\n",
+      path.display().to_string(),
+      day
+    ));
+  }
+  out.push_str("\n/// Every registered day, in order.\n");
+  out.push_str("pub static DAYS: &[registry::Day] = &[\n");
+  for day in &days {
+    let source = fs::read(src.join(format!("{}.rs", day))).expect("Could not read a day");
+    let version = fnv::hash(shared_hash, &source);
+    out.push_str(&format!(
+      "  {}::DAY.with_version(\"{:016x}\"),\n",
+      day, version
+    ));
+  }
+  out.push_str("];\n");
+
+  let dest = Path::new(&env::var("OUT_DIR").unwrap()).join("days.rs");
+  fs::write(dest, out).expect("Could not write days.rs");
+}
+
+fn find_sources(dir: &Path, files: &mut Vec<PathBuf>) {
+  for entry in fs::read_dir(dir).expect("Could not read a source directory") {
+    let path = entry.expect("Could not read a source directory").path();
+    if path.is_dir() {
+      find_sources(&path, files);
+    } else if path.extension().is_some_and(|ext| ext == "rs") {
+      files.push(path);
+    }
+  }
+}
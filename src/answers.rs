@@ -0,0 +1,135 @@
+//! Expected answers, stored in a JSON file keyed by a hash of the input, so
+//! everyone can check their solutions against their own puzzle inputs.
+//!
+//! ```json
+//! {
+//!   "9c3f1e2d4b5a6978": { "day": 1, "part_one": "3412496", "part_two": "5115845" }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fnv;
+use crate::problem::Answer;
+
+pub const DEFAULT_PATH: &str = "answers.json";
+
+/// Hashes an input with 64-bit FNV-1a, ignoring trailing whitespace.
+pub fn hash_input(input: &str) -> String {
+  let hash = fnv::hash(fnv::OFFSET, input.trim_end().as_bytes());
+  format!("{:016x}", hash)
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Entry {
+  day: usize,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  part_one: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  part_two: Option<String>,
+}
+
+impl Entry {
+  fn part_mut(&mut self, part: usize) -> &mut Option<String> {
+    match part {
+      1 => &mut self.part_one,
+      _ => &mut self.part_two,
+    }
+  }
+}
+
+pub struct AnswerFile {
+  path: PathBuf,
+  entries: BTreeMap<String, Entry>,
+}
+
+impl AnswerFile {
+  /// Loads the answers at `path`. A missing file has no answers.
+  pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+    let path = path.as_ref().to_path_buf();
+    let entries = match fs::read_to_string(&path) {
+      Ok(json) => {
+        serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+      }
+      Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+      Err(e) => return Err(e),
+    };
+    Ok(Self { path, entries })
+  }
+
+  pub fn save(&self) -> io::Result<()> {
+    let json =
+      serde_json::to_string_pretty(&self.entries).expect("Entries are always serializable");
+    fs::write(&self.path, json + "\n")
+  }
+
+  /// The expected answer to `part` for `input`, or `Answer::Unknown`.
+  /// Entries recorded for a different day are ignored.
+  pub fn expected(&self, day: usize, part: usize, input: &str) -> Answer {
+    self
+      .entries
+      .get(&hash_input(input))
+      .filter(|entry| entry.day == day)
+      .and_then(|entry| match part {
+        1 => entry.part_one.as_ref(),
+        _ => entry.part_two.as_ref(),
+      })
+      .map_or(Answer::Unknown, |answer| answer.parse().unwrap())
+  }
+
+  /// Stores `answer` as the expected answer to `part` for `input`.
+  pub fn record(&mut self, day: usize, part: usize, input: &str, answer: &Answer) {
+    let entry = self.entries.entry(hash_input(input)).or_default();
+    if entry.day != day {
+      *entry = Entry {
+        day,
+        ..Entry::default()
+      };
+    }
+    *entry.part_mut(part) = Some(answer.to_string());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+  use std::fs;
+
+  use super::{hash_input, AnswerFile};
+  use crate::problem::Answer;
+
+  #[test]
+  fn hash() {
+    // FNV-1a test vectors
+    assert_eq!(hash_input(""), "cbf29ce484222325");
+    assert_eq!(hash_input("a"), "af63dc4c8601ec8c");
+    assert_eq!(hash_input("foobar"), "85944171f73967e8");
+    assert_eq!(hash_input("foobar\n\n"), hash_input("foobar"));
+  }
+
+  #[test]
+  fn record_save_load() {
+    let path = env::temp_dir().join(format!("aoc2019-answers-{}.json", std::process::id()));
+    let mut answers = AnswerFile::load(&path).unwrap();
+    assert!(!answers.expected(1, 1, "12").is_known());
+
+    answers.record(1, 1, "12", &Answer::Integer(2));
+    answers.record(8, 2, "0122", &Answer::grid(vec!["#.", ".#"]));
+    answers.save().unwrap();
+
+    let answers = AnswerFile::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(answers.expected(1, 1, "12\n"), Answer::Integer(2));
+    assert!(!answers.expected(1, 2, "12").is_known());
+    assert!(!answers.expected(2, 1, "12").is_known());
+    assert_eq!(
+      answers.expected(8, 2, "0122"),
+      Answer::grid(vec!["#.", ".#"])
+    );
+  }
+}
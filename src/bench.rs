@@ -0,0 +1,201 @@
+//! Statistics over repeated runs, and saving them to compare later runs
+//! against.
+//!
+//! Saved results are plain text with one line per day and part:
+//! `day part min median stddev`, with durations in nanoseconds.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+  pub min: Duration,
+  pub median: Duration,
+  pub stddev: Duration,
+}
+
+impl Stats {
+  pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+    if samples.is_empty() {
+      return None;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_unstable();
+
+    let mid = sorted.len() / 2;
+    let median = if sorted.len().is_multiple_of(2) {
+      (sorted[mid - 1] + sorted[mid]) / 2
+    } else {
+      sorted[mid]
+    };
+
+    let secs: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+    let mean = secs.iter().sum::<f64>() / secs.len() as f64;
+    let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / secs.len() as f64;
+
+    Some(Self {
+      min: sorted[0],
+      median,
+      stddev: Duration::from_secs_f64(variance.sqrt()),
+    })
+  }
+}
+
+/// The statistics for one part of one day.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BenchResult {
+  pub day: usize,
+  pub part: usize,
+  pub stats: Stats,
+}
+
+/// How a result's median changed relative to a baseline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Comparison {
+  pub baseline: Duration,
+  pub current: Duration,
+}
+
+impl Comparison {
+  /// The relative change, e.g. `0.1` for 10% slower.
+  pub fn change(&self) -> f64 {
+    self.current.as_secs_f64() / self.baseline.as_secs_f64() - 1.
+  }
+
+  /// Whether the result got slower by more than `threshold` (relative).
+  pub fn is_regression(&self, threshold: f64) -> bool {
+    self.change() > threshold
+  }
+}
+
+/// Compares `result` against the baseline for the same day and part.
+pub fn compare(baseline: &[BenchResult], result: &BenchResult) -> Option<Comparison> {
+  baseline
+    .iter()
+    .find(|base| base.day == result.day && base.part == result.part)
+    .map(|base| Comparison {
+      baseline: base.stats.median,
+      current: result.stats.median,
+    })
+}
+
+/// How the time grows with the input size between two measurements, as the
+/// exponent `k` in `time ~ size^k`: about 1 for linear, 2 for quadratic.
+pub fn growth((size1, time1): (usize, Duration), (size2, time2): (usize, Duration)) -> f64 {
+  (time2.as_secs_f64() / time1.as_secs_f64()).ln() / (size2 as f64 / size1 as f64).ln()
+}
+
+pub fn save<P: AsRef<Path>>(path: P, results: &[BenchResult]) -> io::Result<()> {
+  let mut out = String::from("# day part min median stddev (ns)\n");
+  for result in results {
+    out.push_str(&format!(
+      "{} {} {} {} {}\n",
+      result.day,
+      result.part,
+      result.stats.min.as_nanos(),
+      result.stats.median.as_nanos(),
+      result.stats.stddev.as_nanos()
+    ));
+  }
+  fs::write(path, out)
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Vec<BenchResult>> {
+  fs::read_to_string(path)?
+    .lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+    .map(|(i, line)| {
+      parse_line(line).ok_or_else(|| {
+        io::Error::new(
+          io::ErrorKind::InvalidData,
+          format!("line {}: expected `day part min median stddev`", i + 1),
+        )
+      })
+    })
+    .collect()
+}
+
+fn parse_line(line: &str) -> Option<BenchResult> {
+  let fields: Vec<u64> = line
+    .split_whitespace()
+    .map(str::parse)
+    .collect::<Result<_, _>>()
+    .ok()?;
+  match fields[..] {
+    [day, part, min, median, stddev] => Some(BenchResult {
+      day: day as usize,
+      part: part as usize,
+      stats: Stats {
+        min: Duration::from_nanos(min),
+        median: Duration::from_nanos(median),
+        stddev: Duration::from_nanos(stddev),
+      },
+    }),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+  use std::fs;
+  use std::time::Duration;
+
+  use super::{compare, growth, load, save, BenchResult, Stats};
+
+  fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+  }
+
+  #[test]
+  fn stats() {
+    let stats = Stats::from_samples(&[ms(4), ms(2), ms(6), ms(8)]).unwrap();
+    assert_eq!(stats.min, ms(2));
+    assert_eq!(stats.median, ms(5));
+    // sqrt(5)ms
+    assert_eq!(stats.stddev.as_micros(), 2236);
+
+    let stats = Stats::from_samples(&[ms(3)]).unwrap();
+    assert_eq!(stats.median, ms(3));
+    assert_eq!(stats.stddev, Duration::ZERO);
+
+    assert_eq!(Stats::from_samples(&[]), None);
+  }
+
+  #[test]
+  fn save_load_compare() {
+    let result = |day, median| BenchResult {
+      day,
+      part: 1,
+      stats: Stats {
+        min: ms(1),
+        median: ms(median),
+        stddev: Duration::from_nanos(1234),
+      },
+    };
+    let baseline = vec![result(1, 10), result(2, 10)];
+
+    let path = env::temp_dir().join(format!("aoc2019-bench-{}.txt", std::process::id()));
+    save(&path, &baseline).unwrap();
+    let loaded = load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, baseline);
+
+    let slower = compare(&loaded, &result(1, 12)).unwrap();
+    assert!((slower.change() - 0.2).abs() < 1e-9);
+    assert!(slower.is_regression(0.1));
+    assert!(!slower.is_regression(0.25));
+    assert!(!compare(&loaded, &result(2, 9)).unwrap().is_regression(0.1));
+    assert_eq!(compare(&loaded, &result(3, 10)), None);
+  }
+
+  #[test]
+  fn growth_exponent() {
+    assert!((growth((10, ms(3)), (100, ms(30))) - 1.).abs() < 1e-9);
+    assert!((growth((10, ms(3)), (20, ms(12))) - 2.).abs() < 1e-9);
+    assert!(growth((10, ms(3)), (1000, ms(3))).abs() < 1e-9);
+  }
+}
@@ -0,0 +1,161 @@
+//! Cached answers and timings, so repeated runs can skip slow days.
+//!
+//! Entries are keyed by day, part and a hash of the input, and only used if
+//! they were stored by the same version of the day's code (see
+//! `registry::Day::version`).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::answers::hash_input;
+use crate::problem::Answer;
+use crate::timing::Timings;
+
+pub const DEFAULT_PATH: &str = ".cache/results.json";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Entry {
+  version: String,
+  answer: String,
+  parse_ns: u64,
+  solve_ns: u64,
+}
+
+pub struct Cache {
+  path: PathBuf,
+  entries: BTreeMap<String, Entry>,
+}
+
+fn key(day: usize, part: usize, input: &str) -> String {
+  format!("day{:02}-part{}-{}", day, part, hash_input(input))
+}
+
+impl Cache {
+  /// Loads the cache at `path`. A missing or unreadable cache is empty.
+  pub fn load<P: AsRef<Path>>(path: P) -> Self {
+    let path = path.as_ref().to_path_buf();
+    let entries = fs::read_to_string(&path)
+      .ok()
+      .and_then(|json| serde_json::from_str(&json).ok())
+      .unwrap_or_default();
+    Self { path, entries }
+  }
+
+  /// Deletes the cache at `path`, if there is one.
+  pub fn clear<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    match fs::remove_file(path) {
+      Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+      _ => Ok(()),
+    }
+  }
+
+  pub fn save(&self) -> io::Result<()> {
+    if let Some(dir) = self.path.parent() {
+      fs::create_dir_all(dir)?;
+    }
+    let json =
+      serde_json::to_string_pretty(&self.entries).expect("Entries are always serializable");
+    fs::write(&self.path, json + "\n")
+  }
+
+  /// The cached answer to `part` for `input` and how long it took, if it
+  /// was stored by this `version` of the day.
+  pub fn get(
+    &self,
+    day: usize,
+    version: &str,
+    part: usize,
+    input: &str,
+  ) -> Option<(Answer, Timings)> {
+    let entry = self.entries.get(&key(day, part, input))?;
+    if entry.version != version {
+      return None;
+    }
+    let timings = Timings {
+      parse: Duration::from_nanos(entry.parse_ns),
+      solve: Duration::from_nanos(entry.solve_ns),
+    };
+    Some((entry.answer.parse().unwrap(), timings))
+  }
+
+  /// Stores an answer, replacing any entry from another version.
+  pub fn insert(
+    &mut self,
+    day: usize,
+    version: &str,
+    part: usize,
+    input: &str,
+    answer: &Answer,
+    timings: &Timings,
+  ) {
+    let entry = Entry {
+      version: version.to_string(),
+      answer: answer.to_string(),
+      parse_ns: timings.parse.as_nanos() as u64,
+      solve_ns: timings.solve.as_nanos() as u64,
+    };
+    self.entries.insert(key(day, part, input), entry);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+  use std::fs;
+  use std::time::Duration;
+
+  use super::Cache;
+  use crate::problem::Answer;
+  use crate::timing::Timings;
+
+  #[test]
+  fn insert_save_load() {
+    let path = env::temp_dir()
+      .join(format!("aoc2019-cache-{}", std::process::id()))
+      .join("results.json");
+    let timings = Timings {
+      parse: Duration::from_micros(3),
+      solve: Duration::from_millis(40),
+    };
+
+    let mut cache = Cache::load(&path);
+    assert_eq!(cache.get(4, "v1", 1, "123-456"), None);
+    cache.insert(4, "v1", 1, "123-456", &Answer::Integer(9), &timings);
+    cache.insert(
+      8,
+      "v1",
+      2,
+      "0122",
+      &Answer::grid(vec!["#.", ".#"]),
+      &timings,
+    );
+    cache.save().unwrap();
+
+    let mut cache = Cache::load(&path);
+    assert_eq!(
+      cache.get(4, "v1", 1, "123-456\n"),
+      Some((Answer::Integer(9), timings))
+    );
+    assert_eq!(
+      cache.get(8, "v1", 2, "0122").map(|(answer, _)| answer),
+      Some(Answer::grid(vec!["#.", ".#"]))
+    );
+    // Other parts, inputs and versions miss
+    assert_eq!(cache.get(4, "v1", 2, "123-456"), None);
+    assert_eq!(cache.get(4, "v1", 1, "123-457"), None);
+    assert_eq!(cache.get(4, "v2", 1, "123-456"), None);
+
+    cache.insert(4, "v2", 1, "123-456", &Answer::Integer(10), &timings);
+    assert_eq!(cache.get(4, "v1", 1, "123-456"), None);
+
+    Cache::clear(&path).unwrap();
+    assert!(!path.exists());
+    Cache::clear(&path).unwrap();
+    fs::remove_dir(path.parent().unwrap()).unwrap();
+  }
+}
@@ -0,0 +1,834 @@
+//! Command line parsing for the runner.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::log::{Filter, Level};
+
+pub const USAGE: &str = "\
+Usage:
+  aoc2019 [DAYS] [OPTIONS]
+  aoc2019 bench [DAYS] [OPTIONS] [BENCH OPTIONS]
+  aoc2019 watch [DAYS] [OPTIONS] [--interval <MS>]
+  aoc2019 report [DAYS] [OPTIONS] [--out <DIR>]
+  aoc2019 verify [DAYS] [OPTIONS] [VERIFY OPTIONS]
+  aoc2019 generate <DAY> [--size <N>] [--seed <N>] [--out <PATH>]
+  aoc2019 convert <from> <to> [--optimize]
+  aoc2019 new-day <DAY> [--title <TITLE>]
+  aoc2019 help
+
+DAYS is a comma-separated list of days and ranges, e.g. `3`, `1,4`, `3..7`
+(days 3 to 6) or `3..=7` (days 3 to 7). All days run if none are given.
+
+Options:
+  -p, --part <1|2|both>        Which parts to run (default: both)
+  -i, --input <PATH|->         Read the input from PATH, or stdin for `-`.
+                               Needs exactly one day.
+      --log <FILTER>           What to log to stderr, by level and module,
+                               e.g. `day11=debug,intcode=trace` (default: warn)
+  -d, --debug                  Log everything at debug level, like --log debug
+      --color <auto|always|never>
+                               When to color the output (default: auto)
+  -f, --format <text|json|csv> How to print the results (default: text)
+      --answers <PATH>         Expected answers file (default: answers.json)
+      --record                 Save the answers as the expected ones
+  -j, --jobs <N>               Solve parts on N threads, 0 for one per CPU
+                               (default: 1). Not available for bench.
+      --no-cache               Solve every part, even if its answer is cached
+      --clear-cache            Delete the cached answers before running
+
+Answers are cached in .cache/results.json for each input and version of a
+day's code, and cached parts are marked as such. Bench and watch always solve,
+as does any run with --log so that the solvers' logs show up.
+
+Bench options:
+      --runs <N>               Measured runs per part (default: 10)
+      --warmup <N>             Discarded runs before measuring (default: 2)
+      --save <PATH>            Save the results to PATH
+      --compare <PATH>         Compare against results saved to PATH
+      --threshold <PERCENT>    Slowdown flagged as a regression (default: 10)
+      --sizes <N,N,...>        Benchmark generated inputs of these sizes
+                               instead, to see how the times grow. Not
+                               together with --save or --compare.
+      --seed <N>               Seed for generating inputs (default: 0)
+
+Watch re-runs a day whenever its source or inputs change, and shows how the
+answers and timings changed. It does not take --format or --record.
+      --interval <MS>          How often to check for changes (default: 500)
+
+Report writes report.md and report.html, with each part's status, answer and
+timings and the picture answers drawn. It does not take --format.
+      --out <DIR>              Where to write the report (default: report)
+
+Verify checks the days that have a reference solver against it, on their
+inputs and on generated ones. It does not take --format, --record or --jobs.
+      --cases <N>              Generated inputs per day (default: 20)
+      --size <N>               How big the generated inputs are (default: 20)
+      --seed <N>               Seed for generating inputs (default: random)
+
+Generate prints a random input for a day that has an input generator. The
+same seed and size always give the same input.
+      --size <N>               How big the input is (default: 20)
+      --seed <N>               Seed for generating it (default: random)
+  -o, --out <PATH>             Write it to PATH instead of stdout
+
+New-day creates src/dayNN.rs from a template and an empty inputs/dayNN.txt,
+unless either exists.
+      --title <TITLE>          The puzzle's title (default: Day NN)";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+  Run(RunOptions),
+  Bench(RunOptions, BenchOptions),
+  Watch(RunOptions, WatchOptions),
+  Report(RunOptions, ReportOptions),
+  Verify(RunOptions, VerifyOptions),
+  Generate(GenerateOptions),
+  Convert {
+    from: String,
+    to: String,
+    optimize: bool,
+  },
+  NewDay {
+    day: usize,
+    title: String,
+  },
+  Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunOptions {
+  /// The selected days, in order. Empty means every registered day.
+  pub days: Vec<usize>,
+  pub parts: Parts,
+  pub input: Option<Input>,
+  /// What to log, if more than warnings.
+  pub log: Option<Filter>,
+  pub color: ColorChoice,
+  pub format: Format,
+  /// The expected answers file, if not the default.
+  pub answers: Option<PathBuf>,
+  pub record: bool,
+  /// How many threads to solve on, where 0 means one per CPU.
+  pub jobs: usize,
+  pub no_cache: bool,
+  pub clear_cache: bool,
+}
+
+impl Default for RunOptions {
+  fn default() -> Self {
+    Self {
+      days: Vec::new(),
+      parts: Parts::default(),
+      input: None,
+      log: None,
+      color: ColorChoice::default(),
+      format: Format::default(),
+      answers: None,
+      record: false,
+      jobs: 1,
+      no_cache: false,
+      clear_cache: false,
+    }
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchOptions {
+  pub runs: usize,
+  pub warmup: usize,
+  pub save: Option<PathBuf>,
+  pub compare: Option<PathBuf>,
+  /// Relative slowdown of the median that counts as a regression.
+  pub threshold: f64,
+  /// Sizes of generated inputs to benchmark, instead of the real inputs.
+  pub sizes: Vec<usize>,
+  pub seed: u64,
+}
+
+impl Default for BenchOptions {
+  fn default() -> Self {
+    Self {
+      runs: 10,
+      warmup: 2,
+      save: None,
+      compare: None,
+      threshold: 0.1,
+      sizes: Vec::new(),
+      seed: 0,
+    }
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WatchOptions {
+  /// How long to wait between checking for changes.
+  pub interval: Duration,
+}
+
+impl Default for WatchOptions {
+  fn default() -> Self {
+    Self {
+      interval: Duration::from_millis(500),
+    }
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ReportOptions {
+  /// The directory to write `report.md` and `report.html` to.
+  pub out: PathBuf,
+}
+
+impl Default for ReportOptions {
+  fn default() -> Self {
+    Self {
+      out: PathBuf::from("report"),
+    }
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VerifyOptions {
+  /// How many inputs to generate for each day.
+  pub cases: usize,
+  /// Passed to `Problem::generate_input`.
+  pub size: usize,
+  /// The seed to generate inputs from. A random one if not given.
+  pub seed: Option<u64>,
+}
+
+impl Default for VerifyOptions {
+  fn default() -> Self {
+    Self {
+      cases: 20,
+      size: 20,
+      seed: None,
+    }
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GenerateOptions {
+  pub day: usize,
+  /// Passed on to `Problem::generate_input`.
+  pub size: usize,
+  /// A random seed is picked if not given.
+  pub seed: Option<u64>,
+  /// Where to write the input, instead of stdout.
+  pub out: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Parts {
+  One,
+  Two,
+  #[default]
+  Both,
+}
+
+impl Parts {
+  pub fn includes(self, part: usize) -> bool {
+    match self {
+      Parts::One => part == 1,
+      Parts::Two => part == 2,
+      Parts::Both => true,
+    }
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Input {
+  Path(PathBuf),
+  Stdin,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ColorChoice {
+  #[default]
+  Auto,
+  Always,
+  Never,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Format {
+  #[default]
+  Text,
+  Json,
+  Csv,
+}
+
+/// A command line that could not be parsed.
+#[derive(Debug, PartialEq)]
+pub struct UsageError(String);
+
+impl fmt::Display for UsageError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+impl std::error::Error for UsageError {}
+
+fn usage_error<T, M: Into<String>>(message: M) -> Result<T, UsageError> {
+  Err(UsageError(message.into()))
+}
+
+/// Parses the arguments following the program name.
+pub fn parse<I, S>(args: I) -> Result<Command, UsageError>
+where
+  I: IntoIterator<Item = S>,
+  S: Into<String>,
+{
+  let args: Vec<String> = args.into_iter().map(Into::into).collect();
+  match args.first().map(String::as_str) {
+    Some("help") | Some("-h") | Some("--help") => Ok(Command::Help),
+    Some("convert") => match &args[1..] {
+      [from, to] => Ok(Command::Convert {
+        from: from.clone(),
+        to: to.clone(),
+        optimize: false,
+      }),
+      [from, to, flag] if flag == "--optimize" => Ok(Command::Convert {
+        from: from.clone(),
+        to: to.clone(),
+        optimize: true,
+      }),
+      _ => usage_error("convert needs exactly two paths: convert <from> <to> [--optimize]"),
+    },
+    Some("new-day") => match &args[1..] {
+      [day] => parse_day(day).map(|day| Command::NewDay {
+        day,
+        title: format!("Day {:02}", day),
+      }),
+      [day, flag, title] if flag == "--title" => parse_day(day).map(|day| Command::NewDay {
+        day,
+        title: title.clone(),
+      }),
+      _ => usage_error("new-day needs a day: new-day <DAY> [--title <TITLE>]"),
+    },
+    Some("generate") => parse_generate(&args[1..]).map(Command::Generate),
+    Some("bench") => {
+      let mut bench = BenchOptions::default();
+      let options = parse_run(&args[1..], Extra::Bench(&mut bench))?;
+      Ok(Command::Bench(options, bench))
+    }
+    Some("watch") => {
+      let mut watch = WatchOptions::default();
+      let options = parse_run(&args[1..], Extra::Watch(&mut watch))?;
+      Ok(Command::Watch(options, watch))
+    }
+    Some("report") => {
+      let mut report = ReportOptions::default();
+      let options = parse_run(&args[1..], Extra::Report(&mut report))?;
+      Ok(Command::Report(options, report))
+    }
+    Some("verify") => {
+      let mut verify = VerifyOptions::default();
+      let options = parse_run(&args[1..], Extra::Verify(&mut verify))?;
+      Ok(Command::Verify(options, verify))
+    }
+    _ => parse_run(&args, Extra::None).map(Command::Run),
+  }
+}
+
+/// The options of the subcommand being parsed, besides the run options.
+enum Extra<'a> {
+  None,
+  Bench(&'a mut BenchOptions),
+  Watch(&'a mut WatchOptions),
+  Report(&'a mut ReportOptions),
+  Verify(&'a mut VerifyOptions),
+}
+
+impl Extra<'_> {
+  /// Parses `flag` if it is one of the subcommand's own options, returning
+  /// whether it was.
+  fn parse_option<'a>(
+    &mut self,
+    flag: &str,
+    mut value: impl FnMut(&str) -> Result<&'a String, UsageError>,
+  ) -> Result<bool, UsageError> {
+    match (self, flag) {
+      (
+        Extra::Bench(bench),
+        "--runs" | "--warmup" | "--save" | "--compare" | "--threshold" | "--sizes" | "--seed",
+      ) => parse_bench_option(bench, flag, value(flag)?)?,
+      (Extra::Watch(watch), "--interval") => {
+        watch.interval = Duration::from_millis(parse_number(flag, value(flag)?)?)
+      }
+      (Extra::Report(report), "--out") => report.out = PathBuf::from(value(flag)?),
+      (Extra::Verify(verify), "--cases") => verify.cases = parse_number(flag, value(flag)?)?,
+      (Extra::Verify(verify), "--size") => verify.size = parse_number(flag, value(flag)?)?,
+      (Extra::Verify(verify), "--seed") => verify.seed = Some(parse_number(flag, value(flag)?)?),
+      _ => return Ok(false),
+    }
+    Ok(true)
+  }
+}
+
+/// Parses the day selection and options, along with the options of the
+/// subcommand in `extra`.
+fn parse_run(args: &[String], mut extra: Extra) -> Result<RunOptions, UsageError> {
+  let mut options = RunOptions::default();
+  let mut args = args.iter();
+  while let Some(arg) = args.next() {
+    let mut value = |flag: &str| {
+      args
+        .next()
+        .ok_or_else(|| UsageError(format!("{} needs a value", flag)))
+    };
+    match arg.as_str() {
+      "-p" | "--part" => options.parts = parse_parts(value(arg)?)?,
+      "-i" | "--input" => {
+        options.input = Some(match value(arg)?.as_str() {
+          "-" => Input::Stdin,
+          path => Input::Path(PathBuf::from(path)),
+        })
+      }
+      "--log" => options.log = Some(value(arg)?.parse().map_err(UsageError)?),
+      "-d" | "--debug" => options.log = Some(Filter::all(Level::Debug)),
+      "--color" => options.color = parse_color(value(arg)?)?,
+      "-f" | "--format" => options.format = parse_format(value(arg)?)?,
+      "--answers" => options.answers = Some(PathBuf::from(value(arg)?)),
+      "--record" => options.record = true,
+      "-j" | "--jobs" => options.jobs = parse_number(arg, value(arg)?)?,
+      "--no-cache" => options.no_cache = true,
+      "--clear-cache" => options.clear_cache = true,
+      flag if extra.parse_option(flag, &mut value)? => (),
+      flag if flag.starts_with('-') => return usage_error(format!("Unknown option {}", flag)),
+      days => {
+        for day in parse_days(days)? {
+          if !options.days.contains(&day) {
+            options.days.push(day);
+          }
+        }
+      }
+    }
+  }
+
+  match extra {
+    Extra::Bench(_) if options.jobs != 1 => {
+      return usage_error("bench runs on one thread, so it does not take --jobs")
+    }
+    Extra::Bench(bench) if bench.runs == 0 => return usage_error("--runs needs to be at least 1"),
+    Extra::Bench(bench)
+      if !bench.sizes.is_empty() && (bench.save.is_some() || bench.compare.is_some()) =>
+    {
+      return usage_error("--sizes can not be used with --save or --compare")
+    }
+    Extra::Watch(_) if options.format != Format::Text || options.record => {
+      return usage_error("watch does not take --format or --record")
+    }
+    Extra::Verify(_) if options.format != Format::Text || options.record || options.jobs != 1 => {
+      return usage_error("verify does not take --format, --record or --jobs")
+    }
+    Extra::Watch(_) if options.input == Some(Input::Stdin) => {
+      return usage_error("watch can not read the input from stdin")
+    }
+    Extra::Report(_) if options.format != Format::Text => {
+      return usage_error("report does not take --format")
+    }
+    _ => (),
+  }
+  if options.input.is_some() && options.days.len() != 1 {
+    return usage_error("--input needs exactly one day to be selected");
+  }
+  Ok(options)
+}
+
+fn parse_bench_option(bench: &mut BenchOptions, flag: &str, value: &str) -> Result<(), UsageError> {
+  match flag {
+    "--runs" => bench.runs = parse_number(flag, value)?,
+    "--warmup" => bench.warmup = parse_number(flag, value)?,
+    "--save" => bench.save = Some(PathBuf::from(value)),
+    "--compare" => bench.compare = Some(PathBuf::from(value)),
+    "--sizes" => {
+      bench.sizes = value
+        .split(',')
+        .map(|size| parse_number(flag, size))
+        .collect::<Result<_, _>>()?
+    }
+    "--seed" => bench.seed = parse_number(flag, value)?,
+    _ => bench.threshold = parse_number::<f64>(flag, value)? / 100.,
+  }
+  Ok(())
+}
+
+/// Parses `<DAY> [--size <N>] [--seed <N>] [--out <PATH>]`.
+fn parse_generate(args: &[String]) -> Result<GenerateOptions, UsageError> {
+  let mut day = None;
+  let mut options = GenerateOptions {
+    day: 0,
+    size: 20,
+    seed: None,
+    out: None,
+  };
+  let mut args = args.iter();
+  while let Some(arg) = args.next() {
+    let mut value = |flag: &str| {
+      args
+        .next()
+        .ok_or_else(|| UsageError(format!("{} needs a value", flag)))
+    };
+    match arg.as_str() {
+      "--size" => options.size = parse_number(arg, value(arg)?)?,
+      "--seed" => options.seed = Some(parse_number(arg, value(arg)?)?),
+      "-o" | "--out" => options.out = Some(PathBuf::from(value(arg)?)),
+      flag if flag.starts_with('-') => return usage_error(format!("Unknown option {}", flag)),
+      number if day.is_none() => day = Some(parse_day(number)?),
+      _ => return usage_error("generate takes a single day"),
+    }
+  }
+  match day {
+    Some(day) => Ok(GenerateOptions { day, ..options }),
+    None => usage_error("generate needs a day: generate <DAY> [--size <N>]"),
+  }
+}
+
+fn parse_number<T: FromStr>(flag: &str, value: &str) -> Result<T, UsageError> {
+  value
+    .parse()
+    .map_err(|_| UsageError(format!("{} needs a number, got {:?}", flag, value)))
+}
+
+fn parse_parts(value: &str) -> Result<Parts, UsageError> {
+  match value {
+    "1" => Ok(Parts::One),
+    "2" => Ok(Parts::Two),
+    "both" => Ok(Parts::Both),
+    _ => usage_error(format!("Unknown part {:?}, expected 1, 2 or both", value)),
+  }
+}
+
+fn parse_color(value: &str) -> Result<ColorChoice, UsageError> {
+  match value {
+    "auto" => Ok(ColorChoice::Auto),
+    "always" => Ok(ColorChoice::Always),
+    "never" => Ok(ColorChoice::Never),
+    _ => usage_error(format!(
+      "Unknown color choice {:?}, expected auto, always or never",
+      value
+    )),
+  }
+}
+
+fn parse_format(value: &str) -> Result<Format, UsageError> {
+  match value {
+    "text" => Ok(Format::Text),
+    "json" => Ok(Format::Json),
+    "csv" => Ok(Format::Csv),
+    _ => usage_error(format!(
+      "Unknown format {:?}, expected text, json or csv",
+      value
+    )),
+  }
+}
+
+/// Parses `3`, `3..7`, `3..=7` or a comma-separated list of those.
+fn parse_days(spec: &str) -> Result<Vec<usize>, UsageError> {
+  let mut days = Vec::new();
+  for part in spec.split(',') {
+    if let Some((start, end)) = part.split_once("..=") {
+      days.extend(parse_day(start)?..=parse_day(end)?);
+    } else if let Some((start, end)) = part.split_once("..") {
+      days.extend(parse_day(start)?..parse_day(end)?);
+    } else {
+      days.push(parse_day(part)?);
+    }
+  }
+  if days.is_empty() {
+    return usage_error(format!("{:?} does not select any days", spec));
+  }
+  Ok(days)
+}
+
+fn parse_day(day: &str) -> Result<usize, UsageError> {
+  match day.parse() {
+    Ok(num @ 1..=25) => Ok(num),
+    _ => usage_error(format!("{:?} is not a day between 1 and 25", day)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+  use std::time::Duration;
+
+  use super::{
+    parse, BenchOptions, ColorChoice, Command, Filter, Format, GenerateOptions, Input, Level,
+    Parts, ReportOptions, RunOptions, VerifyOptions, WatchOptions,
+  };
+
+  fn run(args: &[&str]) -> RunOptions {
+    match parse(args.iter().copied()) {
+      Ok(Command::Run(options)) => options,
+      other => panic!("expected run options, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn defaults() {
+    assert_eq!(run(&[]), RunOptions::default());
+  }
+
+  #[test]
+  fn day_ranges() {
+    assert_eq!(run(&["3"]).days, vec![3]);
+    assert_eq!(run(&["3..7"]).days, vec![3, 4, 5, 6]);
+    assert_eq!(run(&["3..=7"]).days, vec![3, 4, 5, 6, 7]);
+    assert_eq!(run(&["1,4..6", "2", "4"]).days, vec![1, 4, 5, 2]);
+  }
+
+  #[test]
+  fn options() {
+    let options = run(&[
+      "5", "--part", "2", "-i", "-", "--debug", "--color", "never", "-f", "json",
+    ]);
+    assert_eq!(options.days, vec![5]);
+    assert_eq!(options.parts, Parts::Two);
+    assert_eq!(options.input, Some(Input::Stdin));
+    assert_eq!(options.log, Some(Filter::all(Level::Debug)));
+    assert_eq!(options.color, ColorChoice::Never);
+    assert_eq!(options.format, Format::Json);
+
+    let options = run(&["-i", "other.txt", "9"]);
+    assert_eq!(options.input, Some(Input::Path(PathBuf::from("other.txt"))));
+
+    let options = run(&["--record", "--answers", "mine.json"]);
+    assert!(options.record);
+    assert_eq!(options.answers, Some(PathBuf::from("mine.json")));
+
+    assert_eq!(run(&[]).jobs, 1);
+    assert_eq!(run(&["-j", "4"]).jobs, 4);
+    assert_eq!(run(&["--jobs", "0"]).jobs, 0);
+
+    let options = run(&["--no-cache", "--clear-cache"]);
+    assert!(options.no_cache && options.clear_cache);
+
+    let options = run(&["--log", "intcode=trace,day11=debug"]);
+    let filter = options.log.unwrap();
+    assert_eq!(filter.level("intcode"), Level::Trace);
+    assert_eq!(filter.level("day07"), Level::Warn);
+  }
+
+  #[test]
+  fn subcommands() {
+    assert_eq!(parse(vec!["help"]), Ok(Command::Help));
+    assert_eq!(
+      parse(vec!["convert", "a.txt", "b.icb"]),
+      Ok(Command::Convert {
+        from: "a.txt".to_string(),
+        to: "b.icb".to_string(),
+        optimize: false,
+      })
+    );
+    assert_eq!(
+      parse(vec!["convert", "a.txt", "b.icb", "--optimize"]),
+      Ok(Command::Convert {
+        from: "a.txt".to_string(),
+        to: "b.icb".to_string(),
+        optimize: true,
+      })
+    );
+  }
+
+  #[test]
+  fn new_day() {
+    assert_eq!(
+      parse(vec!["new-day", "12"]),
+      Ok(Command::NewDay {
+        day: 12,
+        title: "Day 12".to_string(),
+      })
+    );
+    assert_eq!(
+      parse(vec!["new-day", "12", "--title", "The N-Body Problem"]),
+      Ok(Command::NewDay {
+        day: 12,
+        title: "The N-Body Problem".to_string(),
+      })
+    );
+  }
+
+  #[test]
+  fn bench_options() {
+    let args = vec![
+      "bench",
+      "4..6",
+      "--runs",
+      "5",
+      "--save",
+      "new.txt",
+      "--compare",
+      "old.txt",
+      "--threshold",
+      "2.5",
+    ];
+    match parse(args) {
+      Ok(Command::Bench(options, bench)) => {
+        assert_eq!(options.days, vec![4, 5]);
+        assert_eq!(bench.runs, 5);
+        assert_eq!(bench.warmup, BenchOptions::default().warmup);
+        assert_eq!(bench.save, Some(PathBuf::from("new.txt")));
+        assert_eq!(bench.compare, Some(PathBuf::from("old.txt")));
+        assert!((bench.threshold - 0.025).abs() < 1e-9);
+        assert!(bench.sizes.is_empty());
+      }
+      other => panic!("expected bench options, got {:?}", other),
+    }
+    match parse(vec!["bench", "3", "--sizes", "10,100,1000", "--seed", "9"]) {
+      Ok(Command::Bench(_, bench)) => {
+        assert_eq!(bench.sizes, vec![10, 100, 1000]);
+        assert_eq!(bench.seed, 9);
+      }
+      other => panic!("expected bench options, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn generate() {
+    assert_eq!(
+      parse(vec!["generate", "6"]),
+      Ok(Command::Generate(GenerateOptions {
+        day: 6,
+        size: 20,
+        seed: None,
+        out: None,
+      }))
+    );
+    assert_eq!(
+      parse(vec![
+        "generate", "--size", "1000", "10", "--seed", "3", "-o", "big.txt"
+      ]),
+      Ok(Command::Generate(GenerateOptions {
+        day: 10,
+        size: 1000,
+        seed: Some(3),
+        out: Some(PathBuf::from("big.txt")),
+      }))
+    );
+  }
+
+  #[test]
+  fn watch_options() {
+    assert_eq!(
+      parse(vec!["watch", "7"]),
+      Ok(Command::Watch(
+        RunOptions {
+          days: vec![7],
+          ..RunOptions::default()
+        },
+        WatchOptions::default()
+      ))
+    );
+    match parse(vec!["watch", "--interval", "100", "-p", "1"]) {
+      Ok(Command::Watch(options, watch)) => {
+        assert_eq!(options.parts, Parts::One);
+        assert_eq!(watch.interval, Duration::from_millis(100));
+      }
+      other => panic!("expected watch options, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn report_options() {
+    assert_eq!(
+      parse(vec!["report"]),
+      Ok(Command::Report(
+        RunOptions::default(),
+        ReportOptions {
+          out: PathBuf::from("report"),
+        }
+      ))
+    );
+    match parse(vec!["report", "1..=3", "--out", "public", "--no-cache"]) {
+      Ok(Command::Report(options, report)) => {
+        assert_eq!(options.days, vec![1, 2, 3]);
+        assert!(options.no_cache);
+        assert_eq!(report.out, PathBuf::from("public"));
+      }
+      other => panic!("expected report options, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn verify_options() {
+    assert_eq!(
+      parse(vec!["verify"]),
+      Ok(Command::Verify(
+        RunOptions::default(),
+        VerifyOptions::default()
+      ))
+    );
+    match parse(vec![
+      "verify", "4,10", "--cases", "5", "--size", "8", "--seed", "42",
+    ]) {
+      Ok(Command::Verify(options, verify)) => {
+        assert_eq!(options.days, vec![4, 10]);
+        assert_eq!(
+          verify,
+          VerifyOptions {
+            cases: 5,
+            size: 8,
+            seed: Some(42),
+          }
+        );
+      }
+      other => panic!("expected verify options, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn usage_errors() {
+    for args in &[
+      vec!["zero"],
+      vec!["0"],
+      vec!["26"],
+      vec!["7..3"],
+      vec!["1", "--part", "3"],
+      vec!["1", "--part"],
+      vec!["--color", "sometimes"],
+      vec!["--format", "xml"],
+      vec!["--input", "day01.txt"],
+      vec!["1,2", "--input", "day01.txt"],
+      vec!["--verbose"],
+      vec!["--log", "day11=loud"],
+      vec!["--log"],
+      vec!["--runs", "5"],
+      vec!["bench", "--runs", "0"],
+      vec!["bench", "--threshold", "ten"],
+      vec!["bench", "--jobs", "4"],
+      vec!["--interval", "100"],
+      vec!["bench", "--interval", "100"],
+      vec!["watch", "--format", "json"],
+      vec!["watch", "--record"],
+      vec!["watch", "1", "--input", "-"],
+      vec!["--out", "public"],
+      vec!["watch", "--out", "public"],
+      vec!["report", "--format", "csv"],
+      vec!["--seed", "1"],
+      vec!["report", "--cases", "1"],
+      vec!["verify", "--seed", "-1"],
+      vec!["verify", "--jobs", "2"],
+      vec!["verify", "--record"],
+      vec!["bench", "--sizes", "10,x"],
+      vec!["bench", "--sizes", "10", "--save", "a.txt"],
+      vec!["generate"],
+      vec!["generate", "6", "7"],
+      vec!["generate", "6", "--jobs", "2"],
+      vec!["--jobs", "-1"],
+      vec!["convert", "a.txt"],
+      vec!["convert", "a.txt", "b.icb", "--fast"],
+      vec!["convert", "--optimize", "a.txt", "b.icb"],
+      vec!["new-day"],
+      vec!["new-day", "26"],
+      vec!["new-day", "12", "--name", "x"],
+    ] {
+      assert!(parse(args.clone()).is_err(), "{:?} should not parse", args);
+    }
+  }
+}
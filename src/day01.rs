@@ -1,19 +1,32 @@
-use crate::problem::Problem;
+//! Fuel for the modules of the spacecraft.
+
+use crate::input;
+use crate::problem::{Answer, Problem, SolveError};
+use crate::rng::Rng;
+use crate::timing;
+
+register_day!(DayOne, 1, "The Tyranny of the Rocket Equation");
 
 #[derive(Default)]
 pub struct DayOne {}
 
 impl DayOne {
-  fn calc_fuel(mass: u32) -> i32 {
-    (mass as f32 / 3.).floor() as i32 - 2
+  /// The fuel needed to launch a module of `mass`, without the fuel's own
+  /// mass. Light modules need none.
+  pub fn fuel(mass: u32) -> u32 {
+    (mass / 3).saturating_sub(2)
   }
 
-  fn fuel(masses: &[u32]) -> u32 {
-    masses
-      .iter()
-      .map(|&m| Self::calc_fuel(m))
-      .filter(|f| f.is_positive())
-      .sum::<i32>() as u32
+  /// The fuel needed for a module of `mass`, counting the fuel needed to
+  /// carry that fuel, and so on.
+  pub fn total_fuel(mass: u32) -> u32 {
+    let mut total = 0;
+    let mut fuel = Self::fuel(mass);
+    while fuel > 0 {
+      total += fuel;
+      fuel = Self::fuel(fuel);
+    }
+    total
   }
 }
 
@@ -22,40 +35,22 @@ impl Problem for DayOne {
     Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some("3412496".to_string())
-  }
-
-  fn part_one(&self, input: &str) -> Option<String> {
-    let masses: Vec<u32> = input
-      .split('\n')
-      .map(|s| s.parse())
-      .filter_map(Result::ok)
-      .collect();
-    Some(Self::fuel(&masses).to_string())
+  fn part_one(&self, input: &str) -> Result<Answer, SolveError> {
+    let masses: Vec<u32> = timing::parse(|| input::ints(input))?;
+    Ok(masses.into_iter().map(Self::fuel).sum::<u32>().into())
   }
 
-  fn soln_two(&self) -> Option<String> {
-    Some("5115845".to_string())
+  fn part_two(&self, input: &str) -> Result<Answer, SolveError> {
+    let masses: Vec<u32> = timing::parse(|| input::ints(input))?;
+    Ok(masses.into_iter().map(Self::total_fuel).sum::<u32>().into())
   }
 
-  fn part_two(&self, input: &str) -> Option<String> {
-    let mut total_fuel = 0;
-    for mass_str in input.split('\n') {
-      let mass: u32 = mass_str.parse().unwrap();
-      let mut fuel: i32 = Self::calc_fuel(mass);
-      if fuel > 0 {
-        total_fuel += fuel;
-      }
-      loop {
-        fuel = Self::calc_fuel(fuel as u32);
-        if fuel <= 0 {
-          break;
-        }
-        total_fuel += fuel;
-      }
-    }
-    Some(total_fuel.to_string())
+  /// `size` module masses, about as heavy as the real ones.
+  fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<String> {
+    let masses: Vec<String> = (0..size)
+      .map(|_| rng.range(1_000..200_000).to_string())
+      .collect();
+    Some(masses.join("\n") + "\n")
   }
 }
 
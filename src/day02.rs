@@ -1,56 +1,61 @@
-use crate::intcode::{self, Intcode};
-use crate::problem::Problem;
+//! The gravity assist program, run with a noun and a verb.
+
+use crate::input;
+use crate::intcode::Intcode;
+use crate::problem::{Answer, Problem, SolveError};
+use crate::timing;
+
+register_day!(DayTwo, 2, "1202 Program Alarm");
 
 #[derive(Default)]
 pub struct DayTwo {}
 
-impl DayTwo {}
+impl DayTwo {
+  /// Runs `program` with the given noun and verb, returning the value left
+  /// at position 0.
+  pub fn run(program: &[i64], noun: i64, verb: i64) -> Result<i64, SolveError> {
+    if program.len() < 3 {
+      return Err(SolveError::invalid("program needs at least 3 cells"));
+    }
+    let mut program = program.to_vec();
+    program[1] = noun;
+    program[2] = verb;
+
+    let mut intcode = Intcode::new(program);
+    intcode.try_run()?;
+    Ok(*intcode.memory.get(0))
+  }
+}
 
 impl Problem for DayTwo {
   fn new() -> Self {
     Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some("2692315".to_string())
-  }
-
-  fn part_one(&self, input: &str) -> Option<String> {
-    let mut program: Vec<i64> = intcode::parse_program(input);
+  fn part_one(&self, input: &str) -> Result<Answer, SolveError> {
+    let program: Vec<i64> = timing::parse(|| input::comma_separated(input))?;
 
     // before running the program,
     // replace position 1 with the value 12 and
     // replace position 2 with the value 2.
-    program[1] = 12;
-    program[2] = 2;
-
-    let mut intcode = Intcode::new(program);
-    intcode.run();
-
-    Some(intcode.memory.get(0).to_string())
-  }
-
-  fn soln_two(&self) -> Option<String> {
-    Some("9507".to_string())
+    Ok(Self::run(&program, 12, 2)?.into())
   }
 
-  fn part_two(&self, input: &str) -> Option<String> {
-    let base_program: Vec<i64> = intcode::parse_program(input);
+  fn part_two(&self, input: &str) -> Result<Answer, SolveError> {
+    let base_program: Vec<i64> = timing::parse(|| input::comma_separated(input))?;
     const TARGET: i64 = 19_690_720;
 
     for noun in 0..99 {
       for verb in 0..99 {
-        let mut program = base_program.clone();
-        program[1] = noun;
-        program[2] = verb;
-        let mut intcode = Intcode::new(program);
-        intcode.run();
-        if TARGET == *intcode.memory.get(0) {
-          return Some((noun * 100 + verb).to_string());
+        if TARGET == Self::run(&base_program, noun, verb)? {
+          return Ok((noun * 100 + verb).into());
         }
       }
     }
-    Some("Not found!".to_string())
+    Err(SolveError::invalid(format!(
+      "no noun and verb produce {}",
+      TARGET
+    )))
   }
 }
 
@@ -1,70 +1,77 @@
-use std::cmp;
+//! Where two wires cross on the grid, found from their paths.
+
 use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::ops::RangeInclusive;
-use std::u32;
 
-use crate::problem::Problem;
+use crate::input;
+use crate::problem::{Answer, Problem, SolveError};
+use crate::rng::Rng;
+use crate::timing;
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Coord {
-  x: i32,
-  y: i32,
+/// A point on the grid, with the central port at the origin and +Y upwards.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Coord {
+  pub x: i32,
+  pub y: i32,
 }
 
 impl Coord {
-  fn new() -> Self {
+  /// The central port.
+  pub fn new() -> Self {
     Coord { x: 0, y: 0 }
   }
 
-  fn from_vals(x: i32, y: i32) -> Self {
+  pub fn from_vals(x: i32, y: i32) -> Self {
     Coord { x, y }
   }
 
-  fn manhattan(self) -> u32 {
-    self.x.abs() as u32 + self.y.abs() as u32
+  /// The Manhattan distance from the central port.
+  pub fn manhattan(self) -> u32 {
+    self.x.unsigned_abs() + self.y.unsigned_abs()
   }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Direction {
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
   Left,
   Right,
   Up,
   Down,
 }
 
-#[derive(Clone, Debug)]
-struct Movement {
-  dir: Direction,
-  amount: usize,
+/// A straight stretch of wire, like `R75`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Movement {
+  pub dir: Direction,
+  pub amount: usize,
 }
 
 impl Movement {
-  fn parse(input: &str) -> Self {
+  pub fn parse(input: &str) -> Result<Self, String> {
     let amount = input
       .get(1..)
-      .unwrap()
+      .unwrap_or_default()
       .parse::<usize>()
-      .expect("Unknown movement value!");
-    match input.chars().nth(0) {
-      Some('L') => Self {
+      .map_err(|e| format!("Unknown movement value {:?}: {}", input, e))?;
+    match input.chars().next() {
+      Some('L') => Ok(Self {
         dir: Direction::Left,
         amount,
-      },
-      Some('R') => Self {
+      }),
+      Some('R') => Ok(Self {
         dir: Direction::Right,
         amount,
-      },
-      Some('U') => Self {
+      }),
+      Some('U') => Ok(Self {
         dir: Direction::Up,
         amount,
-      },
-      Some('D') => Self {
+      }),
+      Some('D') => Ok(Self {
         dir: Direction::Down,
         amount,
-      },
-      _ => panic!("Unknown movement!"),
+      }),
+      _ => Err(format!("Unknown movement {:?}", input)),
     }
   }
 }
@@ -72,8 +79,6 @@ impl Movement {
 struct Segment {
   steps_until: u32,
   dir: Direction,
-  // The position of this segment on the fixed axis.
-  // fixed_axis: i32,
   // The start and end of the segment on the other axis.
   bounds: RangeInclusive<i32>,
 }
@@ -81,88 +86,119 @@ struct Segment {
 // Maps a fixed axis value to the list of wire segments along that axis.
 type WireMap = BTreeMap<i32, Vec<Segment>>;
 
-#[derive(Debug, Eq, Hash, PartialEq)]
-struct Intersection {
-  wire1_steps: u32,
-  wire2_steps: u32,
-  point: Coord,
+/// A wire's segments, indexed by where they lie, so crossings with another
+/// wire are found without comparing every pair of segments.
+#[derive(Default)]
+pub struct WireMaps {
+  // ypos to list of wires in x-axis range
+  horizontals: WireMap,
+  // xpos to list of wires in y-axis range
+  verticals: WireMap,
+}
+
+/// A point where two wires cross, and how many steps each wire takes to
+/// get there.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Intersection {
+  pub wire1_steps: u32,
+  pub wire2_steps: u32,
+  pub point: Coord,
 }
 
+register_day!(DayThree, 3, "Crossed Wires");
+
 #[derive(Default)]
 pub struct DayThree {}
 
 impl DayThree {
-  fn build_maps(horizontals: &mut WireMap, verticals: &mut WireMap, wire: &[Movement]) {
+  /// Parses the first two lines of `input` as wires.
+  pub fn parse_wires(input: &str) -> Result<(Vec<Movement>, Vec<Movement>), SolveError> {
+    let mut wires =
+      input::lines(input).map(|(number, line)| input::split(number, line, ',', Movement::parse));
+    let mut next_wire = || {
+      wires
+        .next()
+        .unwrap_or_else(|| Err(SolveError::invalid("expected two wires")))
+    };
+    Ok((next_wire()?, next_wire()?))
+  }
+
+  /// Lays out `wire` from the central port.
+  pub fn build_maps(wire: &[Movement]) -> WireMaps {
+    let mut maps = WireMaps::default();
     let mut curr_pos = Coord::new();
     let mut steps: u32 = 0;
     for movement in wire {
       match movement.dir {
         Direction::Left => {
-          horizontals.entry(curr_pos.y).or_default();
+          maps.horizontals.entry(curr_pos.y).or_default();
           let end = curr_pos.x;
           curr_pos.x -= movement.amount as i32;
           let start = curr_pos.x;
           let segment = Segment {
             steps_until: steps,
             dir: movement.dir,
-            // fixed_axis: curr_pos.y,
             bounds: start..=end,
           };
-          horizontals
+          maps
+            .horizontals
             .entry(curr_pos.y)
             .and_modify(|w| w.push(segment));
         }
         Direction::Right => {
-          horizontals.entry(curr_pos.y).or_default();
+          maps.horizontals.entry(curr_pos.y).or_default();
           let start = curr_pos.x;
           curr_pos.x += movement.amount as i32;
           let end = curr_pos.x;
           let segment = Segment {
             steps_until: steps,
             dir: movement.dir,
-            // fixed_axis: curr_pos.y,
             bounds: start..=end,
           };
-          horizontals
+          maps
+            .horizontals
             .entry(curr_pos.y)
             .and_modify(|w| w.push(segment));
         }
         Direction::Up => {
-          verticals.entry(curr_pos.x).or_default();
+          maps.verticals.entry(curr_pos.x).or_default();
           let start = curr_pos.y;
           curr_pos.y += movement.amount as i32;
           let end = curr_pos.y;
           let segment = Segment {
             steps_until: steps,
             dir: movement.dir,
-            // fixed_axis: curr_pos.x,
             bounds: start..=end,
           };
-          verticals.entry(curr_pos.x).and_modify(|w| w.push(segment));
+          maps
+            .verticals
+            .entry(curr_pos.x)
+            .and_modify(|w| w.push(segment));
         }
         Direction::Down => {
-          verticals.entry(curr_pos.x).or_default();
+          maps.verticals.entry(curr_pos.x).or_default();
           let end = curr_pos.y;
           curr_pos.y -= movement.amount as i32;
           let start = curr_pos.y;
           let segment = Segment {
             steps_until: steps,
             dir: movement.dir,
-            // fixed_axis: curr_pos.x,
             bounds: start..=end,
           };
-          verticals.entry(curr_pos.x).and_modify(|w| w.push(segment));
+          maps
+            .verticals
+            .entry(curr_pos.x)
+            .and_modify(|w| w.push(segment));
         }
       }
       steps += movement.amount as u32;
     }
+    maps
   }
 
-  fn get_intersections(
-    horizontals: &WireMap,
-    verticals: &WireMap,
-    other_wire: &[Movement],
-  ) -> HashSet<Intersection> {
+  /// Every point where `other_wire` crosses the wire laid out in `maps`,
+  /// including the central port where both start.
+  pub fn get_intersections(maps: &WireMaps, other_wire: &[Movement]) -> HashSet<Intersection> {
     let mut intersections: HashSet<Intersection> = HashSet::new();
     let mut curr_pos = Coord::new();
     let mut other_wire_steps = 0;
@@ -173,9 +209,9 @@ impl DayThree {
           let end = curr_pos.x;
           curr_pos.x -= movement.amount as i32;
           let start = curr_pos.x;
-          for (xpos, segments) in verticals.range(start..=end) {
+          for (xpos, segments) in maps.verticals.range(start..=end) {
             for segment in segments {
-              if segment.bounds.contains(&&curr_pos.y) {
+              if segment.bounds.contains(&curr_pos.y) {
                 let wire1_diff = match segment.dir {
                   Direction::Up => (curr_pos.y - segment.bounds.start()).abs(),
                   Direction::Down => (curr_pos.y - segment.bounds.end()).abs(),
@@ -183,7 +219,7 @@ impl DayThree {
                 };
                 let isec = Intersection {
                   wire1_steps: segment.steps_until + wire1_diff as u32,
-                  wire2_steps: other_wire_steps + (xpos - end).abs() as u32,
+                  wire2_steps: other_wire_steps + (xpos - end).unsigned_abs(),
                   point: Coord::from_vals(*xpos, curr_pos.y),
                 };
                 intersections.insert(isec);
@@ -195,9 +231,9 @@ impl DayThree {
           let start = curr_pos.x;
           curr_pos.x += movement.amount as i32;
           let end = curr_pos.x;
-          for (xpos, segments) in verticals.range(start..=end) {
+          for (xpos, segments) in maps.verticals.range(start..=end) {
             for segment in segments {
-              if segment.bounds.contains(&&curr_pos.y) {
+              if segment.bounds.contains(&curr_pos.y) {
                 let wire1_diff = match segment.dir {
                   Direction::Up => (curr_pos.y - segment.bounds.start()).abs(),
                   Direction::Down => (curr_pos.y - segment.bounds.end()).abs(),
@@ -205,7 +241,7 @@ impl DayThree {
                 };
                 let isec = Intersection {
                   wire1_steps: segment.steps_until + wire1_diff as u32,
-                  wire2_steps: other_wire_steps + (xpos - start).abs() as u32,
+                  wire2_steps: other_wire_steps + (xpos - start).unsigned_abs(),
                   point: Coord::from_vals(*xpos, curr_pos.y),
                 };
                 intersections.insert(isec);
@@ -217,9 +253,9 @@ impl DayThree {
           let start = curr_pos.y;
           curr_pos.y += movement.amount as i32;
           let end = curr_pos.y;
-          for (ypos, segments) in horizontals.range(start..=end) {
+          for (ypos, segments) in maps.horizontals.range(start..=end) {
             for segment in segments {
-              if segment.bounds.contains(&&curr_pos.x) {
+              if segment.bounds.contains(&curr_pos.x) {
                 let wire1_diff = match segment.dir {
                   Direction::Left => (curr_pos.x - segment.bounds.end()).abs(),
                   Direction::Right => (curr_pos.x - segment.bounds.start()).abs(),
@@ -227,7 +263,7 @@ impl DayThree {
                 };
                 let isec = Intersection {
                   wire1_steps: segment.steps_until + wire1_diff as u32,
-                  wire2_steps: other_wire_steps + (ypos - start).abs() as u32,
+                  wire2_steps: other_wire_steps + (ypos - start).unsigned_abs(),
                   point: Coord::from_vals(curr_pos.x, *ypos),
                 };
                 intersections.insert(isec);
@@ -239,9 +275,9 @@ impl DayThree {
           let end = curr_pos.y;
           curr_pos.y -= movement.amount as i32;
           let start = curr_pos.y;
-          for (ypos, segments) in horizontals.range(start..=end) {
+          for (ypos, segments) in maps.horizontals.range(start..=end) {
             for segment in segments {
-              if segment.bounds.contains(&&curr_pos.x) {
+              if segment.bounds.contains(&curr_pos.x) {
                 let wire1_diff = match segment.dir {
                   Direction::Left => (curr_pos.x - segment.bounds.end()).abs(),
                   Direction::Right => (curr_pos.x - segment.bounds.start()).abs(),
@@ -249,7 +285,7 @@ impl DayThree {
                 };
                 let isec = Intersection {
                   wire1_steps: segment.steps_until + wire1_diff as u32,
-                  wire2_steps: other_wire_steps + (ypos - end).abs() as u32,
+                  wire2_steps: other_wire_steps + (ypos - end).unsigned_abs(),
                   point: Coord::from_vals(curr_pos.x, *ypos),
                 };
                 intersections.insert(isec);
@@ -261,9 +297,13 @@ impl DayThree {
 
       other_wire_steps += movement.amount as u32;
     }
-    // println!("Intersections: {:?}", intersections);
     intersections
   }
+
+  /// Every point where the two wires cross, including the central port.
+  pub fn intersections(wire1: &[Movement], wire2: &[Movement]) -> HashSet<Intersection> {
+    Self::get_intersections(&Self::build_maps(wire1), wire2)
+  }
 }
 
 impl Problem for DayThree {
@@ -271,148 +311,77 @@ impl Problem for DayThree {
     Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some("5357".to_string())
-  }
-
-  fn part_one(&self, input: &str) -> Option<String> {
-    let mut wires = input.split('\n');
-    let wire1: Vec<Movement> = wires
-      .next()
-      .unwrap()
-      .split(',')
-      .map(Movement::parse)
-      .collect();
-    let wire2: Vec<Movement> = wires
-      .next()
-      .unwrap()
-      .split(',')
-      .map(Movement::parse)
-      .collect();
-
-    // ypos to list of wires in x-axis range
-    let mut horizontals: WireMap = BTreeMap::new();
-    // xpos to list of wires in y-axis range
-    let mut verticals: WireMap = BTreeMap::new();
-
-    // Populate the WireMaps
-    Self::build_maps(&mut horizontals, &mut verticals, &wire1);
-
-    // println!("Horizontals: {:?}", horizontals);
-    // println!("Verticals: {:?}", verticals);
+  fn part_one(&self, input: &str) -> Result<Answer, SolveError> {
+    let (wire1, wire2) = timing::parse(|| Self::parse_wires(input))?;
 
     // find the closest intersection by manhattan distance
-    Some(
-      Self::get_intersections(&horizontals, &verticals, &wire2)
+    Ok(
+      Self::intersections(&wire1, &wire2)
         .iter()
         .map(|isec| isec.point)
         .map(|coord| coord.manhattan())
         .filter(|&x| x > 0)
-        .fold(u32::MAX, cmp::min)
-        .to_string(),
+        .min()
+        .ok_or_else(|| SolveError::invalid("the wires never cross"))?
+        .into(),
     )
   }
 
-  fn soln_two(&self) -> Option<String> {
-    Some("101956".to_string())
-  }
-
-  fn part_two(&self, input: &str) -> Option<String> {
-    let mut wires = input.split('\n');
-    let wire1: Vec<Movement> = wires
-      .next()
-      .unwrap()
-      .split(',')
-      .map(Movement::parse)
-      .collect();
-    let wire2: Vec<Movement> = wires
-      .next()
-      .unwrap()
-      .split(',')
-      .map(Movement::parse)
-      .collect();
-
-    // ypos to list of wires in x-axis range
-    let mut horizontals: WireMap = BTreeMap::new();
-    // xpos to list of wires in y-axis range
-    let mut verticals: WireMap = BTreeMap::new();
-
-    // Populate the WireMaps
-    Self::build_maps(&mut horizontals, &mut verticals, &wire1);
+  fn part_two(&self, input: &str) -> Result<Answer, SolveError> {
+    let (wire1, wire2) = timing::parse(|| Self::parse_wires(input))?;
 
-    Some(
-      Self::get_intersections(&horizontals, &verticals, &wire2)
+    Ok(
+      Self::intersections(&wire1, &wire2)
         .iter()
         .map(|isec| isec.wire1_steps + isec.wire2_steps)
         .filter(|&x| x > 0)
-        .fold(u32::MAX, cmp::min)
-        .to_string(),
+        .min()
+        .ok_or_else(|| SolveError::invalid("the wires never cross"))?
+        .into(),
     )
   }
+
+  /// Two wires of `size` random moves each, as long as the real ones.
+  fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<String> {
+    let mut wire = || {
+      let moves: Vec<String> = (0..size)
+        .map(|_| {
+          format!(
+            "{}{}",
+            ['L', 'R', 'U', 'D'][rng.below(4)],
+            rng.range(1..1000)
+          )
+        })
+        .collect();
+      moves.join(",")
+    };
+    Some(format!("{}\n{}\n", wire(), wire()))
+  }
 }
 
 #[cfg(test)]
 mod tests {
-  use super::super::problem::Problem;
+  use super::super::problem::{Problem, SolveError};
   use super::DayThree;
 
   #[test]
-  fn part_one_given() {
-    let problem = DayThree {};
-    let answer = problem.part_one(
-      "R8,U5,L5,D3\n\
-       U7,R6,D4,L4",
-    );
-    assert_eq!(answer.unwrap(), "6");
-  }
-
-  #[test]
-  fn part_one_case1() {
-    let problem = DayThree {};
-    let answer = problem.part_one(
-      "R75,D30,R83,U83,L12,D49,R71,U7,L72\n\
-       U62,R66,U55,R34,D71,R55,D58,R83",
-    );
-    assert_eq!(answer.unwrap(), "159");
-  }
-
-  #[test]
-  fn part_one_case2() {
+  fn bad_movement_reports_position() {
     let problem = DayThree {};
-    let answer = problem.part_one(
-      "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\n\
-       U98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+    assert_eq!(
+      problem.part_one("R8,U5\nU7,X6"),
+      Err(SolveError::Parse {
+        line: 2,
+        column: 4,
+        message: "Unknown movement \"X6\"".to_string(),
+      })
     );
-    assert_eq!(answer.unwrap(), "135");
   }
 
   #[test]
-  fn part_two_given() {
+  fn wires_that_never_cross() {
     let problem = DayThree {};
-    let answer = problem.part_two(
-      "R8,U5,L5,D3\n\
-       U7,R6,D4,L4",
-    );
-    assert_eq!(answer.unwrap(), "30");
-  }
-
-  #[test]
-  fn part_two_case1() {
-    let problem = DayThree {};
-    let answer = problem.part_two(
-      "R75,D30,R83,U83,L12,D49,R71,U7,L72\n\
-       U62,R66,U55,R34,D71,R55,D58,R83",
-    );
-    assert_eq!(answer.unwrap(), "610");
-  }
-
-  #[test]
-  fn part_two_case2() {
-    let problem = DayThree {};
-    let answer = problem.part_two(
-      "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\n\
-       U98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
-    );
-    assert_eq!(answer.unwrap(), "410");
+    let error = Err(SolveError::invalid("the wires never cross"));
+    assert_eq!(problem.part_one("U5\nD5"), error);
+    assert_eq!(problem.part_two("U5\nD5"), error);
   }
 }
@@ -1,9 +1,75 @@
-use crate::problem::Problem;
+//! Counting the passwords in a range that follow the Elves' rules.
+
+use crate::input;
+use crate::problem::{Answer, Problem, SolveError};
+use crate::rng::Rng;
+use crate::timing;
+
+register_day!(DayFour, 4, "Secure Container");
 
 #[derive(Default)]
 pub struct DayFour {}
 
 impl DayFour {
+  /// Parses the `start-end` range of the input.
+  pub fn parse_range(input: &str) -> Result<(u32, u32), SolveError> {
+    let line = input::single_line(input)?;
+    match input::split(1, line, '-', input::number)?[..] {
+      [start, end] => Ok((start, end)),
+      _ => Err(SolveError::parse(1, 1, "expected a range like 123-456")),
+    }
+  }
+
+  /// Counts the passwords in `start..=end` whose groups of repeated digits
+  /// pass `groups`, which gets how many times each digit appears. Passwords
+  /// never decrease, so only numbers with non-decreasing digits are built,
+  /// a digit at a time, instead of scanning the whole range.
+  pub fn count_passwords(start: u32, end: u32, groups: fn(&[u32; 10]) -> bool) -> usize {
+    fn build(
+      num: u64,
+      min_digit: u32,
+      left: usize,
+      counts: &mut [u32; 10],
+      visit: &mut dyn FnMut(u64, &[u32; 10]),
+    ) {
+      if left == 0 {
+        visit(num, counts);
+        return;
+      }
+      for digit in min_digit..10 {
+        counts[digit as usize] += 1;
+        build(num * 10 + u64::from(digit), digit, left - 1, counts, visit);
+        counts[digit as usize] -= 1;
+      }
+    }
+
+    let num_digits = |num: u32| num.to_string().len();
+    let range = u64::from(start)..=u64::from(end);
+    let mut count = 0;
+    for len in num_digits(start)..=num_digits(end) {
+      // Only zero itself may start with a zero
+      let first = if len == 1 { 0 } else { 1 };
+      build(0, first, len, &mut [0; 10], &mut |num, counts| {
+        if range.contains(&num) && groups(counts) {
+          count += 1;
+        }
+      });
+    }
+    count
+  }
+
+  /// Whether some digit repeats, the rule for part one. Since digits never
+  /// decrease, repeats are always adjacent.
+  pub fn has_pair(counts: &[u32; 10]) -> bool {
+    counts.iter().any(|&n| n >= 2)
+  }
+
+  /// Whether some digit appears exactly twice, so it is a pair that is not
+  /// part of a larger group.
+  pub fn has_exact_pair(counts: &[u32; 10]) -> bool {
+    counts.contains(&2)
+  }
+
   fn check_part_one(num: u32) -> bool {
     Self::non_decreasing(num) && Self::adjacent_same(num)
   }
@@ -71,44 +137,56 @@ impl Problem for DayFour {
     Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some("1686".to_string())
+  fn part_one(&self, input: &str) -> Result<Answer, SolveError> {
+    let (start, end) = timing::parse(|| Self::parse_range(input))?;
+    let count = Self::count_passwords(start, end, Self::has_pair);
+    Ok(count.into())
   }
 
-  fn part_one(&self, input: &str) -> Option<String> {
-    let mut nums = input.split('-');
-    let start: u32 = nums.next().unwrap().parse().unwrap();
-    let end: u32 = nums.next().unwrap().parse().unwrap();
+  fn part_two(&self, input: &str) -> Result<Answer, SolveError> {
+    let (start, end) = timing::parse(|| Self::parse_range(input))?;
+    let count = Self::count_passwords(start, end, Self::has_exact_pair);
+    Ok(count.into())
+  }
 
-    Some(
+  fn reference_part_one(&self, input: &str) -> Result<Answer, SolveError> {
+    let (start, end) = timing::parse(|| Self::parse_range(input))?;
+
+    Ok(
       (start..=end)
         .filter(|&num| Self::check_part_one(num))
         .count()
-        .to_string(),
+        .into(),
     )
   }
 
-  fn soln_two(&self) -> Option<String> {
-    Some("1145".to_string())
-  }
-
-  fn part_two(&self, input: &str) -> Option<String> {
-    let mut nums = input.split('-');
-    let start: u32 = nums.next().unwrap().parse().unwrap();
-    let end: u32 = nums.next().unwrap().parse().unwrap();
+  fn reference_part_two(&self, input: &str) -> Result<Answer, SolveError> {
+    let (start, end) = timing::parse(|| Self::parse_range(input))?;
 
-    Some(
+    Ok(
       (start..=end)
         .filter(|&num| Self::check_part_two(num))
         .count()
-        .to_string(),
+        .into(),
     )
   }
+
+  /// A range of up to `size` thousand six digit numbers, which may run into
+  /// seven digits.
+  fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<String> {
+    let start = rng.range(100_000..1_000_000);
+    let end = start + rng.range(0..size as u64 * 1000 + 1);
+    Some(format!("{}-{}\n", start, end))
+  }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
   use super::DayFour;
+  use crate::problem::Problem;
+  use crate::rng::Rng;
+  use crate::verify::{self, Outcome};
 
   #[test]
   fn part_one_case1() {
@@ -139,4 +217,22 @@ mod tests {
   fn part_two_case3() {
     assert_eq!(true, DayFour::check_part_two(111_122));
   }
+
+  #[test]
+  fn agrees_with_reference() {
+    let day = DayFour::new();
+    let mut rng = Rng::new(4);
+    let mut inputs = vec!["0-120".to_string(), "999990-1000200".to_string()];
+    inputs.extend((0..5).map(|_| day.generate_input(&mut rng, 5).unwrap()));
+    for input in &inputs {
+      for part in 1..=2 {
+        assert_eq!(
+          verify::compare(&day, part, input),
+          Outcome::Agree,
+          "{}",
+          input
+        );
+      }
+    }
+  }
 }
@@ -1,110 +1,121 @@
-use crate::intcode::{self, Intcode};
-use crate::problem::Problem;
+//! The thermal environment supervision terminal's diagnostic program.
+
+use crate::input;
+use crate::intcode::Intcode;
+use crate::problem::{Answer, Problem, SolveError};
+use crate::timing;
+
+register_day!(DayFive, 5, "Sunny with a Chance of Asteroids");
 
 #[derive(Default)]
 pub struct DayFive {
   inputs: Vec<i64>,
 }
 
-impl DayFive {}
+impl DayFive {
+  /// Runs the diagnostic program with `inputs` (the ID of the system to
+  /// test), returning the diagnostic code it outputs last.
+  pub fn diagnose(program: &[i64], inputs: Vec<i64>) -> Result<i64, SolveError> {
+    let mut intcode = Intcode::new(program.to_vec());
+    intcode.inputs = inputs;
+    intcode.try_run()?;
 
-impl Problem for DayFive {
-  fn new() -> Self {
-    Self { inputs: Vec::new() }
+    intcode
+      .outputs
+      .last()
+      .copied()
+      .ok_or_else(|| SolveError::invalid("diagnostic program did not output anything"))
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some("7839346".to_string())
+  fn run(program: &str, inputs: Vec<i64>) -> Result<Answer, SolveError> {
+    let program: Vec<i64> = timing::parse(|| input::comma_separated(program))?;
+    Ok(Self::diagnose(&program, inputs)?.into())
   }
+}
 
-  fn part_one(&self, program: &str) -> Option<String> {
-    let program: Vec<i64> = intcode::parse_program(program);
+impl Problem for DayFive {
+  fn new() -> Self {
+    Self { inputs: Vec::new() }
+  }
 
+  fn part_one(&self, program: &str) -> Result<Answer, SolveError> {
     let inputs = if self.inputs.is_empty() {
       vec![1]
     } else {
       self.inputs.clone()
     };
 
-    let mut intcode = Intcode::new(program);
-    intcode.inputs = inputs;
-    intcode.run();
-
-    Some(
-      intcode
-        .outputs
-        .last()
-        .map(i64::to_string)
-        .unwrap_or_else(|| "".to_string()),
-    )
-  }
-
-  fn soln_two(&self) -> Option<String> {
-    Some("447803".to_string())
+    Self::run(program, inputs)
   }
 
-  fn part_two(&self, program: &str) -> Option<String> {
-    let program: Vec<i64> = intcode::parse_program(program);
-
+  fn part_two(&self, program: &str) -> Result<Answer, SolveError> {
     let inputs = if self.inputs.is_empty() {
       vec![5]
     } else {
       self.inputs.clone()
     };
 
-    let mut intcode = Intcode::new(program);
-    intcode.inputs = inputs;
-    intcode.run();
-
-    Some(
-      intcode
-        .outputs
-        .last()
-        .map(i64::to_string)
-        .unwrap_or_else(|| "".to_string()),
-    )
+    Self::run(program, inputs)
   }
 }
 
 #[cfg(test)]
 mod tests {
   use super::DayFive;
-  use crate::problem::Problem;
+  use crate::problem::{Answer, Problem, SolveError};
 
   #[test]
   fn part_one_case1() {
     let problem = DayFive { inputs: vec![100] };
-    assert_eq!(problem.part_one("3,0,4,0,99").unwrap(), "100");
+    assert_eq!(
+      problem.part_one("3,0,4,0,99").unwrap(),
+      Answer::Integer(100)
+    );
   }
 
   #[test]
   fn part_one_case2() {
     let problem = DayFive { inputs: vec![] };
-    assert_eq!(problem.part_one("1002,4,3,4,33").unwrap(), "");
+    assert!(matches!(
+      problem.part_one("1002,4,3,4,33"),
+      Err(SolveError::InvalidInput(_))
+    ));
   }
 
   #[test]
   fn part_two_equal_to_8_pos() {
     let problem = DayFive { inputs: vec![10] };
-    assert_eq!(problem.part_two("3,9,8,9,10,9,4,9,99,-1,8").unwrap(), "0");
+    assert_eq!(
+      problem.part_two("3,9,8,9,10,9,4,9,99,-1,8").unwrap(),
+      Answer::Integer(0)
+    );
   }
 
   #[test]
   fn part_two_less_than_8_pos() {
     let problem = DayFive { inputs: vec![5] };
-    assert_eq!(problem.part_two("3,9,7,9,10,9,4,9,99,-1,8").unwrap(), "1");
+    assert_eq!(
+      problem.part_two("3,9,7,9,10,9,4,9,99,-1,8").unwrap(),
+      Answer::Integer(1)
+    );
   }
 
   #[test]
   fn part_two_equal_8_imm() {
     let problem = DayFive { inputs: vec![8] };
-    assert_eq!(problem.part_two("3,3,1108,-1,8,3,4,3,99").unwrap(), "1");
+    assert_eq!(
+      problem.part_two("3,3,1108,-1,8,3,4,3,99").unwrap(),
+      Answer::Integer(1)
+    );
   }
 
   #[test]
   fn part_two_less_than_8_imm() {
     let problem = DayFive { inputs: vec![100] };
-    assert_eq!(problem.part_two("3,3,1107,-1,8,3,4,3,99").unwrap(), "0");
+    assert_eq!(
+      problem.part_two("3,3,1107,-1,8,3,4,3,99").unwrap(),
+      Answer::Integer(0)
+    );
   }
 
   #[test]
@@ -114,7 +125,7 @@ mod tests {
       problem
         .part_two("3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9")
         .unwrap(),
-      "0"
+      Answer::Integer(0)
     );
   }
 
@@ -125,7 +136,7 @@ mod tests {
       problem
         .part_two("3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9")
         .unwrap(),
-      "1"
+      Answer::Integer(1)
     );
   }
 
@@ -136,7 +147,7 @@ mod tests {
       problem
         .part_two("3,3,1105,-1,9,1101,0,0,12,4,12,99,1")
         .unwrap(),
-      "0"
+      Answer::Integer(0)
     );
   }
 
@@ -147,7 +158,7 @@ mod tests {
       problem
         .part_two("3,3,1105,-1,9,1101,0,0,12,4,12,99,1")
         .unwrap(),
-      "1"
+      Answer::Integer(1)
     );
   }
 }
@@ -1,45 +1,122 @@
+//! The map of which object orbits which, as a tree rooted at COM.
+
 use std::collections::{HashMap, HashSet};
 
 use regex::Regex;
 
-use crate::problem::Problem;
+use crate::input;
+use crate::problem::{Answer, Problem, SolveError};
+use crate::rng::Rng;
+use crate::timing;
 
 const COM: &str = "COM";
 const YOU: &str = "YOU";
 const SAN: &str = "SAN";
 
+register_day!(DaySix, 6, "Universal Orbit Map");
+
 #[derive(Default)]
 pub struct DaySix {}
 
 impl DaySix {
-  fn get_height_map<'a>(orbit_map: &HashMap<&'a str, &'a str>) -> HashMap<&'a str, usize> {
+  /// Parses `ABC)XYZ` lines into a map of moon -> the object it orbits.
+  pub fn parse_orbit_map(input: &str) -> Result<HashMap<&str, &str>, SolveError> {
+    let pattern = Regex::new(r"^([A-Z\d]+)\)([A-Z\d]+)$").unwrap();
+
+    // ABC)XYZ == key:XYZ,value:ABC
+    let mut orbit_map = HashMap::new();
+    for (number, line) in input::lines(input) {
+      let caps = pattern.captures(line).ok_or_else(|| {
+        SolveError::parse(number, 1, format!("expected an orbit, got {:?}", line))
+      })?;
+      let earth = caps.get(1).unwrap().as_str();
+      let moon = caps.get(2).unwrap().as_str();
+      orbit_map.insert(moon, earth);
+    }
+    Ok(orbit_map)
+  }
+
+  /// How many orbits, direct and indirect, each object is away from COM.
+  /// Fails if an object's orbits do not lead back to COM.
+  pub fn get_height_map<'a>(
+    orbit_map: &HashMap<&'a str, &'a str>,
+  ) -> Result<HashMap<&'a str, usize>, SolveError> {
     let mut height_map = HashMap::new();
     height_map.insert(COM, 0);
 
     for obj in orbit_map.keys() {
-      Self::get_and_set_height(obj, &mut height_map, orbit_map);
+      Self::get_and_set_height(obj, &mut height_map, orbit_map)?;
     }
     for obj in orbit_map.values() {
-      Self::get_and_set_height(obj, &mut height_map, orbit_map);
+      Self::get_and_set_height(obj, &mut height_map, orbit_map)?;
     }
 
-    height_map
+    Ok(height_map)
+  }
+
+  /// The fewest orbital transfers to get from the object `from` orbits to
+  /// the object `to` orbits, moving one orbit at a time.
+  pub fn transfers(
+    orbit_map: &HashMap<&str, &str>,
+    from: &str,
+    to: &str,
+  ) -> Result<usize, SolveError> {
+    for object in &[from, to] {
+      if !orbit_map.contains_key(object) {
+        return Err(SolveError::invalid(format!(
+          "{} is not orbiting anything",
+          object
+        )));
+      }
+    }
+
+    // path from `to` to COM
+    let mut to_path = Vec::new();
+    let mut to_path_seen = HashSet::new();
+    let mut curr = to;
+    while let Some(parent) = orbit_map.get(curr) {
+      curr = parent;
+      to_path.push(curr);
+      to_path_seen.insert(curr);
+    }
+
+    // Path from `from` to common ancestor
+    let mut curr = from;
+    let mut length = 0;
+    while let Some(parent) = orbit_map.get(curr) {
+      curr = parent;
+      if to_path_seen.contains(curr) {
+        break;
+      }
+      length += 1;
+    }
+
+    // Add up `to` to common ancestor
+    let common = curr;
+    for curr in to_path {
+      if curr == common {
+        break;
+      }
+      length += 1;
+    }
+
+    Ok(length)
   }
 
   fn get_and_set_height<'a>(
     object: &'a str,
     height_map: &mut HashMap<&'a str, usize>,
     orbit_map: &HashMap<&'a str, &'a str>,
-  ) -> usize {
+  ) -> Result<usize, SolveError> {
     match height_map.get(object) {
-      Some(&height) => height,
+      Some(&height) => Ok(height),
       None => {
         let parent = orbit_map
           .get(object)
-          .unwrap_or_else(|| panic!("Parent of {} not found!", object));
-        let height = 1 + Self::get_and_set_height(parent, height_map, orbit_map);
+          .ok_or_else(|| SolveError::invalid(format!("Parent of {} not found!", object)))?;
+        let height = 1 + Self::get_and_set_height(parent, height_map, orbit_map)?;
         height_map.insert(object, height);
-        height
+        Ok(height)
       }
     }
   }
@@ -50,122 +127,63 @@ impl Problem for DaySix {
     Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some("135690".to_string())
-  }
+  fn part_one(&self, input: &str) -> Result<Answer, SolveError> {
+    let orbit_map = timing::parse(|| Self::parse_orbit_map(input))?;
 
-  fn part_one(&self, input: &str) -> Option<String> {
-    let pattern = Regex::new(r"^([A-Z\d]+)\)([A-Z\d]+)$").unwrap();
-    let orbits: Vec<(&str, &str)> = input
-      .split('\n')
-      .map(|line| pattern.captures(line).unwrap())
-      .map(|caps| (caps.get(1).unwrap(), caps.get(2).unwrap()))
-      .map(|(x, y)| (x.as_str(), y.as_str()))
-      .collect();
-
-    // ABC)XYZ == key:XYZ,value:ABC
-    let mut orbit_map = HashMap::new();
-    for (earth, moon) in &orbits {
-      orbit_map.insert(*moon, *earth);
-    }
-
-    let height_map = Self::get_height_map(&orbit_map);
+    let height_map = Self::get_height_map(&orbit_map)?;
     let num_orbits: usize = height_map.values().sum();
 
-    Some(num_orbits.to_string())
-  }
-
-  fn soln_two(&self) -> Option<String> {
-    Some("298".to_string())
+    Ok(num_orbits.into())
   }
 
-  fn part_two(&self, input: &str) -> Option<String> {
-    let pattern = Regex::new(r"^([A-Z\d]+)\)([A-Z\d]+)$").unwrap();
-    let orbits: Vec<(&str, &str)> = input
-      .split('\n')
-      .map(|line| pattern.captures(line).unwrap())
-      .map(|caps| (caps.get(1).unwrap(), caps.get(2).unwrap()))
-      .map(|(x, y)| (x.as_str(), y.as_str()))
-      .collect();
-
+  fn part_two(&self, input: &str) -> Result<Answer, SolveError> {
     // earth)moon == key:moon,value:earth
-    let mut orbit_map = HashMap::new();
-    for (earth, moon) in &orbits {
-      orbit_map.insert(*moon, *earth);
-    }
-
-    // path from SAN to COM
-    let mut santa_path = Vec::new();
-    let mut santa_path_seen = HashSet::new();
-    let mut curr = SAN;
-    while let Some(parent) = orbit_map.get(curr) {
-      curr = parent;
-      santa_path.push(curr);
-      santa_path_seen.insert(curr);
-    }
+    let orbit_map = timing::parse(|| Self::parse_orbit_map(input))?;
+    Ok(Self::transfers(&orbit_map, YOU, SAN)?.into())
+  }
 
-    // Path from YOU to common ancestor
-    let mut curr = YOU;
-    let mut length = 0;
-    while let Some(parent) = orbit_map.get(curr) {
-      curr = parent;
-      if santa_path_seen.contains(curr) {
-        break;
-      }
+  /// An orbit map of `size` objects besides COM, YOU and SAN, in random
+  /// order. Each object orbits COM or an object listed before it, so the
+  /// map is a tree, and YOU and SAN orbit random objects.
+  fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<String> {
+    const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    // Long enough names that random ones rarely collide
+    let mut length = 3;
+    while CHARS.len().pow(length) < size * 8 {
       length += 1;
     }
 
-    // Add up SAN to common ancestor
-    let common = curr;
-    for curr in santa_path {
-      if curr == common {
-        break;
+    let mut objects = vec![COM.to_string()];
+    let mut seen: HashSet<String> = [COM, YOU, SAN].iter().map(|s| s.to_string()).collect();
+    while objects.len() <= size {
+      let name: String = (0..length)
+        .map(|_| CHARS[rng.below(CHARS.len())] as char)
+        .collect();
+      if seen.insert(name.clone()) {
+        objects.push(name);
       }
-      length += 1;
     }
 
-    Some(length.to_string())
+    let mut orbits: Vec<String> = (1..objects.len())
+      .map(|i| format!("{}){}", objects[rng.below(i)], objects[i]))
+      .collect();
+    for moon in &[YOU, SAN] {
+      orbits.push(format!("{}){}", objects[rng.below(objects.len())], moon));
+    }
+    rng.shuffle(&mut orbits);
+    Some(orbits.join("\n") + "\n")
   }
 }
 
 #[cfg(test)]
 mod tests {
   use super::DaySix;
-  use crate::problem::Problem;
-
-  #[test]
-  fn part_one_case1() {
-    let problem = DaySix::new();
-    let input = "COM)B\n\
-                 B)C\n\
-                 C)D\n\
-                 D)E\n\
-                 E)F\n\
-                 B)G\n\
-                 G)H\n\
-                 D)I\n\
-                 E)J\n\
-                 J)K\n\
-                 K)L";
-    assert_eq!(problem.part_one(input).unwrap(), "42");
-  }
+  use crate::problem::{Problem, SolveError};
 
   #[test]
-  fn part_two_case1() {
+  fn bad_orbit_is_a_parse_error() {
     let problem = DaySix::new();
-    let input = "COM)B\n\
-                 B)C\n\
-                 C)D\n\
-                 D)E\n\
-                 E)F\n\
-                 B)G\n\
-                 G)H\n\
-                 D)I\n\
-                 E)J\n\
-                 J)K\n\
-                 K)L\n\
-                 K)YOU\n\
-                 I)SAN";
-    assert_eq!(problem.part_two(input).unwrap(), "4");
+    let err = problem.part_one("COM)B\nB-C").unwrap_err();
+    assert!(matches!(err, SolveError::Parse { line: 2, .. }));
   }
 }
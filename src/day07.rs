@@ -1,27 +1,39 @@
+//! Amplifiers running copies of one Intcode program, each feeding its
+//! output signal to the next.
+
 use std::ops::Range;
 
 use itertools::Itertools;
 
-use crate::intcode::{self, Intcode};
-use crate::problem::Problem;
+use crate::input;
+use crate::intcode::Intcode;
+use crate::problem::{Answer, Problem, SolveError};
+use crate::timing;
+
+register_day!(DaySeven, 7, "Amplification Circuit");
 
 #[derive(Default)]
 pub struct DaySeven {}
 
 impl DaySeven {
-  fn get_output_part_one(phase_settings: &[u8], amp_program: &[i64]) -> i64 {
+  /// The signal out of the last amplifier, when the amplifiers run once in
+  /// series with the given phases, starting from a signal of 0.
+  pub fn series_output(phase_settings: &[u8], amp_program: &[i64]) -> Result<i64, SolveError> {
     let mut signal = 0;
     for &phase in phase_settings.iter() {
       let mut intcode = Intcode::new(amp_program.to_vec());
       intcode.inputs = vec![phase as i64, signal];
-      intcode.run();
-      signal = intcode.outputs[0];
+      intcode.try_run()?;
+      signal = *intcode.outputs.first().ok_or_else(no_signal)?;
     }
     // output of amp sequence is the final signal
-    signal
+    Ok(signal)
   }
 
-  fn get_output_part_two(phase_settings: &[u8], amp_program: &[i64]) -> i64 {
+  /// The last signal out of the last amplifier, when its output is fed
+  /// back into the first until the amplifiers halt. Takes `NUM_AMPS`
+  /// phases.
+  pub fn feedback_output(phase_settings: &[u8], amp_program: &[i64]) -> Result<i64, SolveError> {
     // can probably be optimized...
     let mut amps: [Intcode; NUM_AMPS] = [
       Intcode::new(amp_program.to_vec()),
@@ -45,8 +57,8 @@ impl DaySeven {
       // once at the same time
       {
         let curr_amp: &mut Intcode = &mut amps[amp_iter.next().unwrap()];
-        curr_amp.run();
-        signal = *curr_amp.outputs.last().unwrap();
+        curr_amp.try_run()?;
+        signal = *curr_amp.outputs.last().ok_or_else(no_signal)?;
       }
       {
         let next_amp: &mut Intcode = &mut amps[*(amp_iter.peek().unwrap())];
@@ -59,124 +71,51 @@ impl DaySeven {
     }
 
     // output of amp sequence is the final signal
-    *amps[amps.len() - 1].outputs.last().unwrap()
+    amps[amps.len() - 1]
+      .outputs
+      .last()
+      .copied()
+      .ok_or_else(no_signal)
   }
 }
 
-const NUM_AMPS: usize = 5;
-const SERIES_PHASES: Range<u8> = (0..5);
-const LOOP_PHASES: Range<u8> = (5..10);
+fn no_signal() -> SolveError {
+  SolveError::invalid("amplifier did not output a signal")
+}
+
+/// How many amplifiers there are.
+pub const NUM_AMPS: usize = 5;
+const SERIES_PHASES: Range<u8> = 0..5;
+const LOOP_PHASES: Range<u8> = 5..10;
 
 impl Problem for DaySeven {
   fn new() -> Self {
     Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some("22012".to_string())
-  }
-
   // 1st input: phase setting
   // 2nd input: amp's input signal (prev amp's output)
-  fn part_one(&self, program: &str) -> Option<String> {
-    let amp_program: Vec<i64> = intcode::parse_program(program);
+  fn part_one(&self, program: &str) -> Result<Answer, SolveError> {
+    let amp_program: Vec<i64> = timing::parse(|| input::comma_separated(program))?;
 
     let phase_permutations = SERIES_PHASES.permutations(NUM_AMPS);
     let all_outputs: Vec<i64> = phase_permutations
-      .map(|setting| Self::get_output_part_one(&setting, &amp_program))
-      .collect();
+      .map(|setting| Self::series_output(&setting, &amp_program))
+      .collect::<Result<_, _>>()?;
 
     // find the largest output
-    Some(all_outputs.iter().max().map(i64::to_string).unwrap())
-  }
-
-  fn soln_two(&self) -> Option<String> {
-    Some("4039164".to_string())
+    Ok(Answer::from(*all_outputs.iter().max().unwrap()))
   }
 
-  fn part_two(&self, program: &str) -> Option<String> {
-    let amp_program: Vec<i64> = intcode::parse_program(program);
+  fn part_two(&self, program: &str) -> Result<Answer, SolveError> {
+    let amp_program: Vec<i64> = timing::parse(|| input::comma_separated(program))?;
 
     let phase_permutations = LOOP_PHASES.permutations(NUM_AMPS);
     let all_outputs: Vec<i64> = phase_permutations
-      .map(|setting| Self::get_output_part_two(&setting, &amp_program))
-      .collect();
+      .map(|setting| Self::feedback_output(&setting, &amp_program))
+      .collect::<Result<_, _>>()?;
 
     // find the largest output
-    Some(all_outputs.iter().max().map(i64::to_string).unwrap())
-  }
-}
-
-#[cfg(test)]
-mod tests {
-  use super::DaySeven;
-  use crate::problem::Problem;
-
-  #[test]
-  fn part_one_case1() {
-    let problem = DaySeven::new();
-    assert_eq!(
-      problem
-        .part_one("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0")
-        .unwrap(),
-      "43210"
-    );
-  }
-
-  #[test]
-  fn part_one_case2() {
-    let problem = DaySeven::new();
-    assert_eq!(
-      problem
-        .part_one(
-          "3,23,3,24,1002,24,10,24,1002,23,-1,23,\
-           101,5,23,23,1,24,23,23,4,23,99,0,0"
-        )
-        .unwrap(),
-      "54321"
-    );
-  }
-
-  #[test]
-  fn part_one_case3() {
-    let problem = DaySeven::new();
-    assert_eq!(
-      problem
-        .part_one(
-          "3,31,3,32,1002,32,10,32,1001,31,-2,31,1007,31,0,33,\
-           1002,33,7,33,1,33,31,31,1,32,31,31,4,31,99,0,0,0"
-        )
-        .unwrap(),
-      "65210"
-    );
-  }
-
-  #[test]
-  fn part_two_case1() {
-    let problem = DaySeven::new();
-    assert_eq!(
-      problem
-        .part_two(
-          "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,\
-           27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5"
-        )
-        .unwrap(),
-      "139629729"
-    );
-  }
-
-  #[test]
-  fn part_two_case2() {
-    let problem = DaySeven::new();
-    assert_eq!(
-      problem
-        .part_two(
-          "3,52,1001,52,-5,52,3,53,1,52,56,54,1007,54,5,55,1005,55,26,1001,54,\
-           -5,54,1105,1,12,1,53,54,53,1008,54,0,55,1001,55,1,55,2,53,55,53,4,\
-           53,1001,56,-1,56,1005,56,6,99,0,0,0,0,10"
-        )
-        .unwrap(),
-      "18216"
-    );
+    Ok(Answer::from(*all_outputs.iter().max().unwrap()))
   }
 }
@@ -1,13 +1,22 @@
+//! Images in the Space Image Format: layers of digits, where 0 is black,
+//! 1 is white and 2 is transparent.
+
 use std::collections::HashMap;
 
-use crate::problem::Problem;
+use crate::input;
+use crate::problem::{Answer, Problem, SolveError};
+use crate::rng::Rng;
+use crate::timing;
 
-const WIDTH: usize = 25;
-const HEIGHT: usize = 6;
+/// The size of the password image.
+pub const WIDTH: usize = 25;
+pub const HEIGHT: usize = 6;
 
 mod helpers {
+  /// One layer's pixels, row by row.
   pub type Layer = [u8];
 
+  /// An image of layers stacked front to back.
   pub struct Image {
     width: usize,
     height: usize,
@@ -15,6 +24,8 @@ mod helpers {
   }
 
   impl Image {
+    /// An image from every layer's pixels in turn, which must make up whole
+    /// layers.
     pub fn new(width: usize, height: usize, data: Vec<u8>) -> Self {
       Self {
         width,
@@ -33,13 +44,16 @@ mod helpers {
       self.data.get(start..end).expect("Invalid layer size!")
     }
 
-    pub fn iter(&self) -> Layers {
+    /// The layers, front to back.
+    pub fn iter(&self) -> Layers<'_> {
       Layers {
-        image: &self,
+        image: self,
         next_layer: 0,
       }
     }
 
+    /// Returns the first non-transparent color at (`x`, `y`),
+    /// or transparent (2) if there is none.
     pub fn get_pixel(&self, x: usize, y: usize) -> u8 {
       for layer in self.iter() {
         let color = layer[self.width * y + x];
@@ -47,7 +61,7 @@ mod helpers {
           return color;
         }
       }
-      panic!("No color found at ({}, {})!", x, y);
+      2
     }
   }
 
@@ -71,35 +85,37 @@ mod helpers {
   }
 }
 
+register_day!(DayEight, 8, "Space Image Format");
+
 #[derive(Default)]
-pub struct DayEight {
-  debug: bool,
+pub struct DayEight {}
+
+impl DayEight {
+  /// Parses a password image of `WIDTH` by `HEIGHT` layers.
+  pub fn parse_image(input: &str) -> Result<Image, SolveError> {
+    let pixels = input::digits(input)?;
+    if pixels.is_empty() || !pixels.len().is_multiple_of(WIDTH * HEIGHT) {
+      return Err(SolveError::invalid(format!(
+        "{} pixels do not make up {}x{} layers",
+        pixels.len(),
+        WIDTH,
+        HEIGHT
+      )));
+    }
+    Ok(Image::new(WIDTH, HEIGHT, pixels))
+  }
 }
 
-impl DayEight {}
-
-use helpers::{Image, Layer};
+pub use helpers::{Image, Layer, Layers};
 
 impl Problem for DayEight {
   fn new() -> Self {
-    Self { debug: false }
-  }
-
-  fn debug() -> Self {
-    Self { debug: true }
-  }
-
-  fn soln_one(&self) -> Option<String> {
-    Some("2562".to_string())
+    Self {}
   }
 
   #[allow(clippy::naive_bytecount)]
-  fn part_one(&self, input: &str) -> Option<String> {
-    let pixels: Vec<u8> = input
-      .chars()
-      .map(|c| c.to_digit(10).unwrap() as u8)
-      .collect();
-    let image = Image::new(WIDTH, HEIGHT, pixels);
+  fn part_one(&self, input: &str) -> Result<Answer, SolveError> {
+    let image = timing::parse(|| Self::parse_image(input))?;
 
     let mut layer_zeros: HashMap<&Layer, usize> = HashMap::new();
     for layer in image.iter() {
@@ -110,38 +126,35 @@ impl Problem for DayEight {
     let ones = min_layer.iter().filter(|&&p| p == 1).count();
     let twos = min_layer.iter().filter(|&&p| p == 2).count();
 
-    Some((ones * twos).to_string())
+    Ok((ones * twos).into())
   }
 
-  fn part_two(&self, input: &str) -> Option<String> {
-    let pixels: Vec<u8> = input
-      .chars()
-      .map(|c| c.to_digit(10).unwrap() as u8)
-      .collect();
-    let image = Image::new(WIDTH, HEIGHT, pixels);
+  fn part_two(&self, input: &str) -> Result<Answer, SolveError> {
+    let image = timing::parse(|| Self::parse_image(input))?;
 
-    for y in 0..HEIGHT {
-      for x in 0..WIDTH {
-        let out = match image.get_pixel(x, y) {
+    let rows = (0..HEIGHT).map(|y| {
+      (0..WIDTH)
+        .map(|x| match image.get_pixel(x, y) {
           0 => ' ',
           1 => '*',
           _ => '?',
-        };
-        if self.debug {
-          print!("{}", out);
-        }
-      }
-      if self.debug {
-        println!();
-      }
-    }
+        })
+        .collect::<String>()
+    });
 
-    Some("Done".to_string())
+    Ok(Answer::grid(rows))
   }
-}
 
-#[cfg(test)]
-mod tests {
-  // use super::DayEight;
-  // use crate::problem::Problem;
+  /// An image of `size` layers (at least one). The last layer has no
+  /// transparent pixels, so the whole image shows.
+  fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<String> {
+    let layers = size.max(1);
+    let mut pixels = String::with_capacity(layers * WIDTH * HEIGHT + 1);
+    for layer in 0..layers {
+      let colors = if layer + 1 == layers { 2 } else { 3 };
+      pixels.extend((0..WIDTH * HEIGHT).map(|_| (b'0' + rng.below(colors) as u8) as char));
+    }
+    pixels.push('\n');
+    Some(pixels)
+  }
 }
@@ -1,40 +1,58 @@
-use crate::intcode::{self, Intcode};
-use crate::problem::Problem;
+//! The BOOST program, which checks the Intcode VM's relative mode and large
+//! numbers.
+
+use num_bigint::BigInt;
+
+use crate::input;
+use crate::intcode::{Intcode, Word as _};
+use crate::problem::{Answer, Problem, SolveError};
+use crate::timing;
+
+/// The BOOST program has to support large numbers, so it runs on
+/// arbitrary-precision words rather than relying on `i64` being big enough.
+type Word = BigInt;
+
+register_day!(DayNine, 9, "Sensor Boost");
 
 #[derive(Default)]
 pub struct DayNine {}
 
-impl DayNine {}
+impl DayNine {
+  /// Runs the BOOST program in the given mode (1 to test, 2 to boost the
+  /// sensors), returning its last output.
+  pub fn boost(program: &[Word], mode: i64) -> Result<Word, SolveError> {
+    let mut intcode = Intcode::from_program(program.to_vec());
+    intcode.inputs.push(Word::from(mode));
+    intcode.try_run()?;
 
-impl Problem for DayNine {
-  fn new() -> Self {
-    Self {}
+    intcode
+      .outputs
+      .pop()
+      .ok_or_else(|| SolveError::invalid("BOOST program did not output anything"))
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some("2662308295".to_string())
+  fn run(program: &str, mode: i64) -> Result<Answer, SolveError> {
+    let boost_program: Vec<Word> = timing::parse(|| input::comma_separated(program))?;
+    let output = Self::boost(&boost_program, mode)?;
+    Ok(
+      output
+        .to_i64()
+        .map_or_else(|| Answer::Text(output.to_string()), Answer::Integer),
+    )
   }
+}
 
-  fn part_one(&self, program: &str) -> Option<String> {
-    let amp_program: Vec<i64> = intcode::parse_program(program);
-    let mut intcode = Intcode::new(amp_program.to_vec());
-    intcode.inputs.push(1); // test mode
-    intcode.run();
-
-    intcode.outputs.last().map(|x| x.to_string())
+impl Problem for DayNine {
+  fn new() -> Self {
+    Self {}
   }
 
-  fn soln_two(&self) -> Option<String> {
-    Some("63441".to_string())
+  fn part_one(&self, program: &str) -> Result<Answer, SolveError> {
+    Self::run(program, 1) // test mode
   }
 
-  fn part_two(&self, program: &str) -> Option<String> {
-    let amp_program: Vec<i64> = intcode::parse_program(program);
-    let mut intcode = Intcode::new(amp_program.to_vec());
-    intcode.inputs.push(2); // sensor boost mode
-    intcode.run();
-
-    intcode.outputs.last().map(|x| x.to_string())
+  fn part_two(&self, program: &str) -> Result<Answer, SolveError> {
+    Self::run(program, 2) // sensor boost mode
   }
 }
 
@@ -55,7 +73,8 @@ mod tests {
         .map(|x| x.to_string())
         .collect::<Vec<String>>()
         .join(","),
-    );
+    )
+    .unwrap();
     let mut intcode = Intcode::new(program);
     intcode.run();
     assert_eq!(intcode.outputs, input);
@@ -67,15 +86,6 @@ mod tests {
     let output = problem
       .part_one("1102,34915192,34915192,7,4,7,99,0")
       .unwrap();
-    assert_eq!(output.chars().count(), 16);
-  }
-
-  #[test]
-  fn part_one_case3() {
-    let problem = DayNine::new();
-    assert_eq!(
-      problem.part_one("104,1125899906842624,99").unwrap(),
-      "1125899906842624"
-    );
+    assert_eq!(output.to_string().chars().count(), 16);
   }
 }
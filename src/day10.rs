@@ -1,3 +1,6 @@
+//! Asteroid fields: which asteroid sees the most others, and the order a
+//! laser at it vaporizes them in.
+
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
@@ -8,11 +11,17 @@ use std::slice;
 
 use fraction::Decimal;
 
-use crate::problem::Problem;
+use crate::input;
+use crate::problem::{Answer, Problem, SolveError};
+use crate::rng::Rng;
+use crate::timing;
+
+pub type NumVisible = usize;
 
-type NumVisible = usize;
+/// The asteroid to bet on is the 200th one vaporized.
+const VAPORIZED: usize = 200;
 
-#[derive(Clone, Copy, Eq, Hash, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
 struct Angle {
   angle: Decimal,
 }
@@ -32,8 +41,16 @@ impl Ord for Angle {
   }
 }
 
-#[derive(Clone, Copy, Eq, Hash, PartialEq)]
-struct Asteroid(i16, i16);
+impl PartialOrd for Angle {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+/// An asteroid's (x, y) position. +Y is upwards, so the asteroid in row `r`
+/// of the map has `y == -r`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Asteroid(pub i16, pub i16);
 
 impl Asteroid {
   /// Returns the angle in radians in range [0, 2*PI) going
@@ -51,29 +68,39 @@ impl Asteroid {
   }
 }
 
+register_day!(DayTen, 10, "Monitoring Station");
+
 #[derive(Default)]
 pub struct DayTen {}
 
 impl DayTen {
   /// The problem statement uses a flipped Y axis (+Y is downwards).
   /// This function flips the Y coordinates.
-  fn parse_asteroids(input: &str) -> Vec<Asteroid> {
+  pub fn parse_asteroids(input: &str) -> Result<Vec<Asteroid>, SolveError> {
     let mut asteroids = Vec::new();
-    for (y, row) in input.split('\n').enumerate() {
-      for (x, val) in row.chars().enumerate() {
+    for (y, row) in input::grid(input)?.iter().enumerate() {
+      for (x, &val) in row.iter().enumerate() {
         match val {
           '.' => {}
           '#' => {
             asteroids.push(Asteroid(x as i16, -(y as i16)));
           }
-          _ => panic!("Unexpected char {}", val),
+          _ => {
+            return Err(SolveError::parse(
+              y + 1,
+              x + 1,
+              format!("Unexpected char {:?}", val),
+            ))
+          }
         }
       }
     }
-    asteroids
+    Ok(asteroids)
   }
 
-  fn best_position(asteroids: &[Asteroid]) -> (Asteroid, NumVisible) {
+  /// The asteroid that sees the most others, and how many it sees. Ties go
+  /// to the first one in reading order.
+  pub fn best_position(asteroids: &[Asteroid]) -> Result<(Asteroid, NumVisible), SolveError> {
     // position -> num asteroids visible
     let mut visible: HashMap<Asteroid, NumVisible> = HashMap::with_capacity(asteroids.len());
 
@@ -90,53 +117,39 @@ impl DayTen {
       visible.insert(a1, uniq_angles.len());
     }
 
-    let (best, num) = visible.iter().max_by_key(|(_, &num)| num).unwrap();
-    (*best, *num)
-  }
-}
-
-impl Problem for DayTen {
-  fn new() -> Self {
-    Self {}
-  }
-
-  fn soln_one(&self) -> Option<String> {
-    Some("329".to_string())
-  }
-
-  fn part_one(&self, input: &str) -> Option<String> {
-    let asteroids = DayTen::parse_asteroids(input);
-    let (_, num_visible) = DayTen::best_position(&asteroids);
-    Some(num_visible.to_string())
-  }
-
-  fn soln_two(&self) -> Option<String> {
-    Some("512".to_string())
+    // Not whichever asteroid the hash map yields first
+    asteroids
+      .iter()
+      .rev()
+      .map(|asteroid| (*asteroid, visible[asteroid]))
+      .max_by_key(|&(_, num)| num)
+      .ok_or_else(|| SolveError::invalid("no asteroids found"))
   }
 
-  fn part_two(&self, input: &str) -> Option<String> {
-    let asteroids = DayTen::parse_asteroids(input);
-    let (best_asteroid, _) = DayTen::best_position(&asteroids);
-
+  /// The `n`th asteroid (counting from 1) vaporized by a laser at `station`
+  /// that starts pointing up and rotates clockwise, vaporizing the closest
+  /// asteroid in its way. None if there are fewer than `n` to vaporize.
+  pub fn vaporized(asteroids: &[Asteroid], station: Asteroid, n: usize) -> Option<Asteroid> {
     // sorted angle -> asteroids at that angle
     let mut at_angle: BTreeMap<Angle, Vec<Asteroid>> = BTreeMap::new();
-    // asteroid -> distance to best_asteroid
-    let mut distances: HashMap<Asteroid, f32> = HashMap::with_capacity(asteroids.len() - 1);
+    // asteroid -> distance to station
+    let mut distances: HashMap<Asteroid, f32> = HashMap::with_capacity(asteroids.len());
 
     for &asteroid in asteroids.iter() {
-      if asteroid == best_asteroid {
+      if asteroid == station {
         continue; // skip the same asteroid
       }
-      let angle = best_asteroid.angle(asteroid);
-      let distance = best_asteroid.distance(asteroid);
+      let angle = station.angle(asteroid);
+      let distance = station.distance(asteroid);
       at_angle
         .entry(angle)
         .and_modify(|vec| vec.push(asteroid))
         .or_insert_with(|| vec![asteroid]);
       distances.insert(asteroid, distance);
     }
-
-    let num_angles = at_angle.keys().len();
+    if n == 0 || n > distances.len() {
+      return None;
+    }
 
     // sort the asteroids by their distance.
     at_angle.values_mut().for_each(|vec| {
@@ -150,145 +163,196 @@ impl Problem for DayTen {
       .collect();
 
     // consume each angle's iterator, cycling through the ordered angle keys
-    let mut curr: &Asteroid = &Asteroid(0, 0);
-    for num in 0..200 {
-      curr = at_angle_iter
-        .iter_mut()
-        .nth(num % num_angles)
-        .unwrap()
-        .1
-        .next()
-        .unwrap();
-      // println!("{} => ({},{})", num + 1, curr.0, -curr.1);
+    // and skipping the angles that have no asteroids left. There are at
+    // least n asteroids besides the station, so this ends.
+    let mut vaporized = 0;
+    loop {
+      for curr in at_angle_iter.values_mut().filter_map(Iterator::next) {
+        vaporized += 1;
+        if vaporized == n {
+          return Some(*curr);
+        }
+      }
     }
+  }
 
-    Some((curr.0 * 100 - curr.1).to_string())
+  /// The asteroids as (x, y) with +Y downwards, as in the input.
+  fn reference_asteroids(input: &str) -> Result<Vec<(i64, i64)>, SolveError> {
+    let asteroids = DayTen::parse_asteroids(input)?;
+    Ok(
+      asteroids
+        .iter()
+        .map(|a| (i64::from(a.0), -i64::from(a.1)))
+        .collect(),
+    )
   }
-}
 
-fn f32_cmp(a: f32, b: f32) -> Ordering {
-  a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+  /// Whether no asteroid in `field` lies exactly on the line between `from`
+  /// and `to`.
+  fn in_sight(field: &HashSet<(i64, i64)>, from: (i64, i64), to: (i64, i64)) -> bool {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let steps = gcd(dx.abs(), dy.abs());
+    (1..steps).all(|k| !field.contains(&(from.0 + dx / steps * k, from.1 + dy / steps * k)))
+  }
+
+  /// The best position by checking every pair of asteroids for blockers.
+  fn reference_best(asteroids: &[(i64, i64)]) -> Result<((i64, i64), NumVisible), SolveError> {
+    let field: HashSet<(i64, i64)> = asteroids.iter().copied().collect();
+    let visible = |from| {
+      asteroids
+        .iter()
+        .filter(|&&to| to != from && DayTen::in_sight(&field, from, to))
+        .count()
+    };
+    asteroids
+      .iter()
+      .rev()
+      .map(|&asteroid| (asteroid, visible(asteroid)))
+      .max_by_key(|&(_, num)| num)
+      .ok_or_else(|| SolveError::invalid("no asteroids found"))
+  }
 }
 
-#[cfg(test)]
-mod tests {
-  use super::DayTen;
-  use crate::problem::Problem;
+impl Problem for DayTen {
+  fn new() -> Self {
+    Self {}
+  }
 
-  #[test]
-  fn part_one_given() {
-    let problem = DayTen::new();
-    let input = ".#..#\n\
-                 .....\n\
-                 #####\n\
-                 ....#\n\
-                 ...##";
-    // 3,4
-    assert_eq!(problem.part_one(input).unwrap(), "8".to_string());
+  fn part_one(&self, input: &str) -> Result<Answer, SolveError> {
+    let asteroids = timing::parse(|| DayTen::parse_asteroids(input))?;
+    let (_, num_visible) = DayTen::best_position(&asteroids)?;
+    Ok(num_visible.into())
   }
 
-  #[test]
-  fn part_one_case1() {
-    let problem = DayTen::new();
-    let input = "......#.#.\n\
-                 #..#.#....\n\
-                 ..#######.\n\
-                 .#.#.###..\n\
-                 .#..#.....\n\
-                 ..#....#.#\n\
-                 #..#....#.\n\
-                 .##.#..###\n\
-                 ##...#..#.\n\
-                 .#....####";
-    // 5,8
-    assert_eq!(problem.part_one(input).unwrap(), "33".to_string());
+  fn part_two(&self, input: &str) -> Result<Answer, SolveError> {
+    let asteroids = timing::parse(|| DayTen::parse_asteroids(input))?;
+    if asteroids.len() <= VAPORIZED {
+      return Err(SolveError::invalid(format!(
+        "need more than {} asteroids",
+        VAPORIZED
+      )));
+    }
+    let (station, _) = DayTen::best_position(&asteroids)?;
+    let asteroid = DayTen::vaporized(&asteroids, station, VAPORIZED).unwrap();
+    Ok((i64::from(asteroid.0) * 100 - i64::from(asteroid.1)).into())
   }
 
-  #[test]
-  fn part_one_case2() {
-    let problem = DayTen::new();
-    let input = "#.#...#.#.\n\
-                 .###....#.\n\
-                 .#....#...\n\
-                 ##.#.#.#.#\n\
-                 ....#.#.#.\n\
-                 .##..###.#\n\
-                 ..#...##..\n\
-                 ..##....##\n\
-                 ......#...\n\
-                 .####.###.";
-    // 1,2
-    assert_eq!(problem.part_one(input).unwrap(), "35".to_string());
+  fn reference_part_one(&self, input: &str) -> Result<Answer, SolveError> {
+    let asteroids = timing::parse(|| DayTen::reference_asteroids(input))?;
+    let (_, num_visible) = DayTen::reference_best(&asteroids)?;
+    Ok(num_visible.into())
   }
 
-  #[test]
-  fn part_one_case3() {
-    let problem = DayTen::new();
-    let input = ".#..#..###\n\
-                 ####.###.#\n\
-                 ....###.#.\n\
-                 ..###.##.#\n\
-                 ##.##.#.#.\n\
-                 ....###..#\n\
-                 ..#.#..#.#\n\
-                 #..#.#.###\n\
-                 .##...##.#\n\
-                 .....#.#..";
-    // 6,3
-    assert_eq!(problem.part_one(input).unwrap(), "41".to_string());
+  /// Sweeps the laser round one full rotation at a time, vaporizing every
+  /// asteroid in sight in clockwise order.
+  fn reference_part_two(&self, input: &str) -> Result<Answer, SolveError> {
+    let asteroids = timing::parse(|| DayTen::reference_asteroids(input))?;
+    if asteroids.len() <= VAPORIZED {
+      return Err(SolveError::invalid(format!(
+        "need more than {} asteroids",
+        VAPORIZED
+      )));
+    }
+    let (station, _) = DayTen::reference_best(&asteroids)?;
+
+    let mut field: HashSet<(i64, i64)> = asteroids.iter().copied().collect();
+    field.remove(&station);
+    let mut vaporized = 0;
+    loop {
+      let mut in_sight: Vec<(i64, i64)> = field
+        .iter()
+        .copied()
+        .filter(|&asteroid| DayTen::in_sight(&field, station, asteroid))
+        .collect();
+      in_sight.sort_by(|a, b| {
+        clockwise(
+          (a.0 - station.0, a.1 - station.1),
+          (b.0 - station.0, b.1 - station.1),
+        )
+      });
+      for asteroid in in_sight {
+        field.remove(&asteroid);
+        vaporized += 1;
+        if vaporized == VAPORIZED {
+          return Ok((asteroid.0 * 100 + asteroid.1).into());
+        }
+      }
+    }
   }
 
-  #[test]
-  fn part_one_case4() {
-    let problem = DayTen::new();
-    let input = ".#..##.###...#######\n\
-                 ##.############..##.\n\
-                 .#.######.########.#\n\
-                 .###.#######.####.#.\n\
-                 #####.##.#.##.###.##\n\
-                 ..#####..#.#########\n\
-                 ####################\n\
-                 #.####....###.#.#.##\n\
-                 ##.#################\n\
-                 #####.##.###..####..\n\
-                 ..######..##.#######\n\
-                 ####.##.####...##..#\n\
-                 .#####..#.######.###\n\
-                 ##...#.##########...\n\
-                 #.##########.#######\n\
-                 .####.#.###.###.#.##\n\
-                 ....##.##.###..#####\n\
-                 .#.#.###########.###\n\
-                 #.#.#.#####.####.###\n\
-                 ###.##.####.##.#..##";
-    // 11,13
-    assert_eq!(problem.part_one(input).unwrap(), "210".to_string());
+  /// A `size` by `size` field with a random density of asteroids, topped up
+  /// to more than `VAPORIZED` asteroids if there is room so part two has an
+  /// answer.
+  fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<String> {
+    let density = rng.range(20..70) as f64 / 100.;
+    let mut cells: Vec<bool> = (0..size * size).map(|_| rng.chance(density)).collect();
+    let mut empty: Vec<usize> = (0..cells.len()).filter(|&i| !cells[i]).collect();
+    rng.shuffle(&mut empty);
+    let wanted = (VAPORIZED + 1).min(cells.len());
+    let missing = wanted.saturating_sub(cells.len() - empty.len());
+    for &i in &empty[..missing] {
+      cells[i] = true;
+    }
+
+    let mut field = String::new();
+    for y in 0..size {
+      let row = &cells[y * size..(y + 1) * size];
+      field.extend(row.iter().map(|&asteroid| if asteroid { '#' } else { '.' }));
+      field.push('\n');
+    }
+    Some(field)
   }
+}
+
+/// Orders directions clockwise starting from straight up, where +Y is
+/// downwards.
+fn clockwise(a: (i64, i64), b: (i64, i64)) -> Ordering {
+  // Directions from straight up to just before straight down come first
+  let half = |(x, y): (i64, i64)| usize::from(!(x > 0 || (x == 0 && y < 0)));
+  half(a)
+    .cmp(&half(b))
+    .then_with(|| (a.1 * b.0).cmp(&(a.0 * b.1)))
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+  if b == 0 {
+    a
+  } else {
+    gcd(b, a % b)
+  }
+}
+
+fn f32_cmp(a: f32, b: f32) -> Ordering {
+  a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{DayTen, VAPORIZED};
+  use crate::problem::Problem;
+  use crate::rng::Rng;
+  use crate::verify::{self, Outcome};
 
   #[test]
-  fn part_two_case1() {
-    let problem = DayTen::new();
-    let input = ".#..##.###...#######\n\
-                 ##.############..##.\n\
-                 .#.######.########.#\n\
-                 .###.#######.####.#.\n\
-                 #####.##.#.##.###.##\n\
-                 ..#####..#.#########\n\
-                 ####################\n\
-                 #.####....###.#.#.##\n\
-                 ##.#################\n\
-                 #####.##.###..####..\n\
-                 ..######..##.#######\n\
-                 ####.##.####...##..#\n\
-                 .#####..#.######.###\n\
-                 ##...#.##########...\n\
-                 #.##########.#######\n\
-                 .####.#.###.###.#.##\n\
-                 ....##.##.###..#####\n\
-                 .#.#.###########.###\n\
-                 #.#.#.#####.####.###\n\
-                 ###.##.####.##.#..##";
-    // 11,13
-    assert_eq!(problem.part_two(input).unwrap(), "802".to_string());
+  fn agrees_with_reference() {
+    let day = DayTen::new();
+    let mut rng = Rng::new(10);
+    for size in &[3, 8, 15, 20, 24] {
+      for _ in 0..3 {
+        let input = day.generate_input(&mut rng, *size).unwrap();
+        assert_eq!(
+          verify::compare(&day, 1, &input),
+          Outcome::Agree,
+          "\n{}",
+          input
+        );
+        let part_two = verify::compare(&day, 2, &input);
+        if size * size > VAPORIZED {
+          assert_eq!(part_two, Outcome::Agree, "\n{}", input);
+        } else {
+          assert!(matches!(part_two, Outcome::Skipped(_)), "{:?}", part_two);
+        }
+      }
+    }
   }
 }
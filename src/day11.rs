@@ -1,17 +1,25 @@
+//! The hull painting robot, driven by an Intcode program that sees the
+//! color of the panel under it.
+
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
-use crate::intcode::{self, Intcode};
-use crate::problem::Problem;
+use crate::input;
+use crate::intcode::Intcode;
+use crate::problem::{Answer, Problem, SolveError};
+use crate::timing;
 
+/// A panel on the hull, with the robot starting at the origin and +Y
+/// upwards.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Position {
-  x: i32,
-  y: i32,
+pub struct Position {
+  pub x: i32,
+  pub y: i32,
 }
 
 impl Position {
-  fn update(&mut self, dir: Direction) {
+  /// Moves one panel in `dir`.
+  pub fn update(&mut self, dir: Direction) {
     match dir {
       Direction::Left => self.x -= 1,
       Direction::Up => self.y += 1,
@@ -43,8 +51,8 @@ impl PartialOrd for Position {
   }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Direction {
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
   Left,
   Up,
   Right,
@@ -54,49 +62,52 @@ enum Direction {
 impl Direction {
   /// 0 => left 90 deg
   /// 1 => right 90 deg
-  /// _ => panic
-  fn turn(self, val: u8) -> Self {
-    match self {
+  /// _ => None
+  pub fn turn(self, val: i64) -> Option<Self> {
+    let turned = match self {
       Self::Left => match val {
         0 => Self::Down,
         1 => Self::Up,
-        _ => panic!("Cannot turn {}!", val),
+        _ => return None,
       },
       Self::Up => match val {
         0 => Self::Left,
         1 => Self::Right,
-        _ => panic!("Cannot turn {}!", val),
+        _ => return None,
       },
       Self::Right => match val {
         0 => Self::Up,
         1 => Self::Down,
-        _ => panic!("Cannot turn {}!", val),
+        _ => return None,
       },
       Self::Down => match val {
         0 => Self::Right,
         1 => Self::Left,
-        _ => panic!("Cannot turn {}!", val),
+        _ => return None,
       },
-    }
+    };
+    Some(turned)
   }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Color {
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Color {
   Black,
   White,
 }
 
 impl Color {
-  fn from(val: u8) -> Self {
+  /// The color for the robot's input and output values.
+  pub fn from(val: i64) -> Option<Self> {
     match val {
-      0 => Color::Black,
-      1 => Color::White,
-      _ => panic!("No color for {}!", val),
+      0 => Some(Color::Black),
+      1 => Some(Color::White),
+      _ => None,
     }
   }
 
-  fn repr(self) -> &'static str {
+  /// How the color is drawn: `.` for black, `#` for white.
+  pub fn repr(self) -> &'static str {
     match self {
       Self::Black => ".",
       Self::White => "#",
@@ -104,85 +115,87 @@ impl Color {
   }
 }
 
-const DEFAULT_COLOR: Color = Color::Black;
+/// The color of the panels that have not been painted.
+pub const DEFAULT_COLOR: Color = Color::Black;
 
-struct Robot {
+/// A robot that reads the color under it, paints the panel and moves on, as
+/// its program tells it to.
+pub struct Robot {
   intcode: Intcode,
   curr_pos: Position,
   curr_dir: Direction,
 }
 
 impl Robot {
-  fn new(program: Vec<i64>) -> Self {
+  /// A robot at the origin facing up, running `program`.
+  pub fn new(program: Vec<i64>) -> Self {
     Self {
-      intcode: Intcode::new(program.to_vec()),
+      intcode: Intcode::new(program),
       curr_pos: Position { x: 0, y: 0 },
       curr_dir: Direction::Up,
     }
   }
 
-  /// Runs the robot.
+  /// Where the robot is.
+  pub fn position(&self) -> Position {
+    self.curr_pos
+  }
+
+  /// Runs the robot until its program halts.
   /// `painted` is a map of panel position -> painted color.
-  fn run(&mut self, painted: &mut HashMap<Position, Color>) {
+  pub fn run(&mut self, painted: &mut HashMap<Position, Color>) -> Result<(), SolveError> {
     while !self.intcode.has_halted {
       self
         .intcode
         .inputs
         .push(*painted.get(&self.curr_pos).unwrap_or(&DEFAULT_COLOR) as i64);
-      self.intcode.run();
-      let idx = self.intcode.outputs.len() - 2;
+      self.intcode.try_run()?;
+      let idx = self
+        .intcode
+        .outputs
+        .len()
+        .checked_sub(2)
+        .ok_or_else(|| SolveError::invalid("robot needs a color and a turn"))?;
       let outputs = self.intcode.outputs.get(idx..).unwrap();
 
       // First output is color
-      let color = Color::from(*outputs.get(0).unwrap() as u8);
+      let color = Color::from(outputs[0])
+        .ok_or_else(|| SolveError::invalid(format!("No color for {}!", outputs[0])))?;
       painted.insert(self.curr_pos, color);
 
       // Second output is movement
-      self.curr_dir = self.curr_dir.turn(*outputs.get(1).unwrap() as u8);
+      self.curr_dir = self
+        .curr_dir
+        .turn(outputs[1])
+        .ok_or_else(|| SolveError::invalid(format!("Cannot turn {}!", outputs[1])))?;
       self.curr_pos.update(self.curr_dir);
     }
+    Ok(())
   }
 }
 
-#[derive(Default)]
-pub struct DayEleven {
-  debug: bool,
-}
-
-impl DayEleven {}
-
-impl Problem for DayEleven {
-  fn new() -> Self {
-    Self { debug: false }
-  }
+register_day!(DayEleven, 11, "Space Police");
 
-  fn debug() -> Self {
-    Self { debug: true }
-  }
-
-  fn soln_one(&self) -> Option<String> {
-    Some("2418".to_string())
-  }
+#[derive(Default)]
+pub struct DayEleven {}
 
-  fn part_one(&self, input: &str) -> Option<String> {
-    let program: Vec<i64> = intcode::parse_program(input);
+impl DayEleven {
+  /// Runs a robot with `program`, starting on a panel of color `start`, and
+  /// returns the color of every panel it painted at least once.
+  pub fn paint(program: Vec<i64>, start: Color) -> Result<HashMap<Position, Color>, SolveError> {
     let mut robot = Robot::new(program);
-
     let mut painted: HashMap<Position, Color> = HashMap::new();
-    robot.run(&mut painted);
-
-    Some(painted.len().to_string())
+    painted.insert(robot.position(), start);
+    robot.run(&mut painted)?;
+    Ok(painted)
   }
 
-  fn part_two(&self, input: &str) -> Option<String> {
-    let program: Vec<i64> = intcode::parse_program(input);
-    let mut robot = Robot::new(program);
-
-    let mut painted: HashMap<Position, Color> = HashMap::new();
-    // Set the starting WHITE panel
-    painted.insert(robot.curr_pos, Color::White);
-    robot.run(&mut painted);
-
+  /// Draws the painted panels, top row first, over just the area that was
+  /// painted.
+  pub fn render(painted: &HashMap<Position, Color>) -> Vec<String> {
+    if painted.is_empty() {
+      return Vec::new();
+    }
     let mut all_positions: Vec<&Position> = painted.keys().collect();
     all_positions.sort_unstable(); // unstable is faster
 
@@ -190,37 +203,52 @@ impl Problem for DayEleven {
     let max_x: i32 = all_positions.iter().max_by_key(|pos| pos.x).unwrap().x;
     let min_y: i32 = all_positions.iter().min_by_key(|pos| pos.y).unwrap().y;
     let max_y: i32 = all_positions.iter().max_by_key(|pos| pos.y).unwrap().y;
-    if self.debug {
-      println!("min_x: {}", min_x);
-      println!("max_x: {}", max_x);
-      println!("min_y: {}", min_y);
-      println!("max_y: {}", max_y);
-    }
+    debug!(
+      "x from {} to {}, y from {} to {}",
+      min_x, max_x, min_y, max_y
+    );
     let mut pos_iter = all_positions.iter();
     let mut curr_pos = pos_iter.next();
 
+    let mut rows = Vec::new();
     for y in (min_y..=max_y).rev() {
       // Have to reverse the range as Y axis is flipped
+      let mut row = String::new();
       for x in min_x..=max_x {
         if curr_pos.is_some() && curr_pos.unwrap().x == x && curr_pos.unwrap().y == y {
-          if self.debug {
-            print!("{}", painted.get(curr_pos.unwrap()).unwrap().repr());
-          }
+          row.push_str(painted.get(curr_pos.unwrap()).unwrap().repr());
           curr_pos = pos_iter.next();
-        } else if self.debug {
-          print!("{}", DEFAULT_COLOR.repr());
+        } else {
+          row.push_str(DEFAULT_COLOR.repr());
         }
       }
-      if self.debug {
-        println!();
-      }
+      rows.push(row);
     }
+    rows
+  }
+}
+
+impl Problem for DayEleven {
+  fn new() -> Self {
+    Self {}
+  }
+
+  fn part_one(&self, input: &str) -> Result<Answer, SolveError> {
+    let program: Vec<i64> = timing::parse(|| input::comma_separated(input))?;
+    let painted = Self::paint(program, DEFAULT_COLOR)?;
+    Ok(painted.len().into())
+  }
 
-    Some("Done".to_string())
+  fn part_two(&self, input: &str) -> Result<Answer, SolveError> {
+    let program: Vec<i64> = timing::parse(|| input::comma_separated(input))?;
+    // Start on a white panel
+    let painted = Self::paint(program, Color::White)?;
+    Ok(Answer::Grid(Self::render(&painted)))
   }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
   use super::Position;
 
@@ -0,0 +1,58 @@
+use std::error;
+use std::fmt;
+
+use crate::intcode::IntcodeError;
+
+/// Why a part could not be solved.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SolveError {
+  /// The input could not be parsed. `line` and `column` are 1-based.
+  Parse {
+    line: usize,
+    column: usize,
+    message: String,
+  },
+  /// The input parsed fine but does not have the shape the puzzle needs.
+  InvalidInput(String),
+  /// An Intcode program failed to run.
+  Intcode(IntcodeError),
+  /// The part has not been solved yet.
+  Unimplemented,
+}
+
+impl SolveError {
+  pub fn parse<M: fmt::Display>(line: usize, column: usize, message: M) -> Self {
+    SolveError::Parse {
+      line,
+      column,
+      message: message.to_string(),
+    }
+  }
+
+  pub fn invalid<M: fmt::Display>(message: M) -> Self {
+    SolveError::InvalidInput(message.to_string())
+  }
+}
+
+impl fmt::Display for SolveError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SolveError::Parse {
+        line,
+        column,
+        message,
+      } => write!(f, "Parse error at {}:{}: {}", line, column, message),
+      SolveError::InvalidInput(message) => write!(f, "Invalid input: {}", message),
+      SolveError::Intcode(err) => write!(f, "Intcode error: {}", err),
+      SolveError::Unimplemented => write!(f, "unimplemented"),
+    }
+  }
+}
+
+impl error::Error for SolveError {}
+
+impl From<IntcodeError> for SolveError {
+  fn from(err: IntcodeError) -> Self {
+    SolveError::Intcode(err)
+  }
+}
@@ -0,0 +1,133 @@
+//! Puzzle examples stored as files, so adding an example is just adding a
+//! file. Each file has a header with the expected answers (either part may
+//! be left out), a `---` line, and then the input:
+//!
+//! ```text
+//! part_one: 6
+//! part_two: 30
+//! ---
+//! R8,U5,L5,D3
+//! U7,R6,D4,L4
+//! ```
+//!
+//! Examples are found the same way as inputs: `dayNN.txt` and every `.txt`
+//! file in `dayNN/`.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::input;
+use crate::problem::Answer;
+
+pub const DEFAULT_DIR: &str = "tests/examples";
+
+const SEPARATOR: &str = "---";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Example {
+  pub part_one: Option<Answer>,
+  pub part_two: Option<Answer>,
+  pub input: String,
+}
+
+impl Example {
+  /// The expected answer to `part`, if the example has one.
+  pub fn expected(&self, part: usize) -> Option<&Answer> {
+    match part {
+      1 => self.part_one.as_ref(),
+      _ => self.part_two.as_ref(),
+    }
+  }
+}
+
+/// Parses an example file. Blank lines and `#` comments are allowed in the
+/// header.
+pub fn parse(text: &str) -> Result<Example, String> {
+  let mut example = Example {
+    part_one: None,
+    part_two: None,
+    input: String::new(),
+  };
+
+  let mut rest = text;
+  let mut line_number = 0;
+  loop {
+    let (line, next) = match rest.find('\n') {
+      Some(end) => (&rest[..end], &rest[end + 1..]),
+      None if rest.is_empty() => return Err(format!("missing a `{}` line", SEPARATOR)),
+      None => (rest, ""),
+    };
+    rest = next;
+    line_number += 1;
+
+    let line = line.trim();
+    if line == SEPARATOR {
+      break;
+    }
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+
+    let (key, value) = line
+      .split_once(':')
+      .ok_or_else(|| format!("line {}: expected `key: value`", line_number))?;
+    let answer = Some(value.trim().parse().unwrap());
+    match key.trim() {
+      "part_one" => example.part_one = answer,
+      "part_two" => example.part_two = answer,
+      key => return Err(format!("line {}: unknown key {:?}", line_number, key)),
+    }
+  }
+
+  if example.part_one.is_none() && example.part_two.is_none() {
+    return Err("no expected answers".to_string());
+  }
+  example.input = rest.to_string();
+  Ok(example)
+}
+
+/// Finds the example files for every day in `dir`, in day order.
+pub fn find<P: AsRef<Path>>(dir: P) -> io::Result<Vec<(usize, PathBuf)>> {
+  let mut examples = Vec::new();
+  for day in 1..=25 {
+    for path in input::find_inputs(&dir, day)? {
+      examples.push((day, path));
+    }
+  }
+  Ok(examples)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::parse;
+  use crate::problem::Answer;
+
+  #[test]
+  fn parse_example() {
+    let example = parse("# from the puzzle\npart_two: 30\n\n---\nR8,U5\nU7,R6\n").unwrap();
+    assert_eq!(example.expected(1), None);
+    assert_eq!(example.expected(2), Some(&Answer::Integer(30)));
+    assert_eq!(example.input, "R8,U5\nU7,R6\n");
+
+    let example = parse("part_one: CGL\n---\n").unwrap();
+    assert_eq!(example.part_one, Some(Answer::Text("CGL".to_string())));
+    assert_eq!(example.input, "");
+  }
+
+  #[test]
+  fn parse_errors() {
+    assert_eq!(
+      parse("part_one: 6\nR8,U5"),
+      Err("line 2: expected `key: value`".to_string())
+    );
+    assert_eq!(
+      parse("part_three: 6\n---\n"),
+      Err("line 1: unknown key \"part_three\"".to_string())
+    );
+    assert_eq!(
+      parse("part_one: 6\n"),
+      Err("missing a `---` line".to_string())
+    );
+    assert_eq!(parse("---\n1"), Err("no expected answers".to_string()));
+  }
+}
@@ -0,0 +1,14 @@
+//! 64-bit FNV-1a hashing, for input keys and day versions.
+//!
+//! `build.rs` includes this file with `#[path]`, so it must not use anything
+//! else from the crate.
+
+pub const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0100_0000_01b3;
+
+/// Continues `hash` with `bytes`. Start from [`OFFSET`].
+pub fn hash(hash: u64, bytes: &[u8]) -> u64 {
+  bytes.iter().fold(hash, |hash, &byte| {
+    (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+  })
+}
@@ -0,0 +1,278 @@
+//! Finding the puzzle inputs for a day, and parsing them.
+//!
+//! Inputs may have `\r\n` line endings, trailing whitespace and trailing
+//! blank lines. The parsing helpers ignore all of those, and report errors
+//! with the 1-based line and column they happened at.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::error::SolveError;
+
+pub const DEFAULT_DIR: &str = "inputs";
+
+/// Finds the inputs for `day` in `dir`: `dayNN.txt` and every `.txt` file in
+/// `dayNN/`, in that order. Files in the directory are sorted by name.
+pub fn find_inputs<P: AsRef<Path>>(dir: P, day: usize) -> io::Result<Vec<PathBuf>> {
+  let dir = dir.as_ref();
+  let mut inputs = Vec::new();
+
+  let single = dir.join(format!("day{:02}.txt", day));
+  if single.is_file() {
+    inputs.push(single);
+  }
+
+  let day_dir = dir.join(format!("day{:02}", day));
+  if day_dir.is_dir() {
+    let mut files: Vec<PathBuf> = fs::read_dir(&day_dir)?
+      .map(|entry| entry.map(|entry| entry.path()))
+      .collect::<io::Result<_>>()?;
+    files.retain(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"));
+    files.sort();
+    inputs.extend(files);
+  }
+
+  Ok(inputs)
+}
+
+/// Normalizes `\r\n` line endings to `\n`, and strips whitespace from the
+/// end of each line and of the input.
+pub fn normalize(input: &str) -> String {
+  let mut normalized: String = lines(input)
+    .map(|(_, line)| line)
+    .collect::<Vec<_>>()
+    .join("\n");
+  if !normalized.is_empty() {
+    normalized.push('\n');
+  }
+  normalized
+}
+
+/// The lines of `input` with their 1-based line numbers, without trailing
+/// whitespace or trailing blank lines.
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+  input
+    .trim_end()
+    .lines()
+    .enumerate()
+    .map(|(i, line)| (i + 1, line.trim_end()))
+}
+
+/// The only line of `input`.
+pub fn single_line(input: &str) -> Result<&str, SolveError> {
+  let mut lines = lines(input);
+  match (lines.next(), lines.next()) {
+    (Some((_, line)), None) => Ok(line),
+    (None, _) => Err(SolveError::invalid("the input is empty")),
+    (Some(_), Some((number, _))) => Err(SolveError::parse(number, 1, "expected a single line")),
+  }
+}
+
+/// Parses a number, for use with `split`.
+pub fn number<T>(text: &str) -> Result<T, String>
+where
+  T: FromStr,
+  T::Err: fmt::Display,
+{
+  text
+    .parse()
+    .map_err(|e| format!("{:?} is not a number: {}", text, e))
+}
+
+/// Splits line `line_number` on `separator` and parses each field, ignoring
+/// whitespace around the fields. Errors point at the field's column.
+pub fn split<T, F>(
+  line_number: usize,
+  line: &str,
+  separator: char,
+  mut parse: F,
+) -> Result<Vec<T>, SolveError>
+where
+  F: FnMut(&str) -> Result<T, String>,
+{
+  let mut column = 1;
+  line
+    .split(separator)
+    .map(|field| {
+      let start = column + field.len() - field.trim_start().len();
+      column += field.len() + separator.len_utf8();
+      parse(field.trim()).map_err(|e| SolveError::parse(line_number, start, e))
+    })
+    .collect()
+}
+
+/// One number per line.
+pub fn ints<T>(input: &str) -> Result<Vec<T>, SolveError>
+where
+  T: FromStr,
+  T::Err: fmt::Display,
+{
+  lines(input)
+    .map(|(number, line)| {
+      let column = 1 + line.len() - line.trim_start().len();
+      self::number(line.trim()).map_err(|e| SolveError::parse(number, column, e))
+    })
+    .collect()
+}
+
+/// Comma-separated numbers, e.g. an Intcode program. They may span several
+/// lines.
+pub fn comma_separated<T>(input: &str) -> Result<Vec<T>, SolveError>
+where
+  T: FromStr,
+  T::Err: fmt::Display,
+{
+  let mut numbers = Vec::new();
+  for (number, line) in lines(input) {
+    let line = line.strip_suffix(',').unwrap_or(line);
+    numbers.extend(split(number, line, ',', self::number)?);
+  }
+  Ok(numbers)
+}
+
+/// A single line of digits, e.g. `0120`.
+pub fn digits(input: &str) -> Result<Vec<u8>, SolveError> {
+  single_line(input)?
+    .chars()
+    .enumerate()
+    .map(|(i, c)| {
+      c.to_digit(10)
+        .map(|digit| digit as u8)
+        .ok_or_else(|| SolveError::parse(1, i + 1, format!("{:?} is not a digit", c)))
+    })
+    .collect()
+}
+
+/// A rectangular grid of characters, indexed by row then column.
+pub fn grid(input: &str) -> Result<Vec<Vec<char>>, SolveError> {
+  let mut rows: Vec<Vec<char>> = Vec::new();
+  for (number, line) in lines(input) {
+    let row: Vec<char> = line.chars().collect();
+    if let Some(first) = rows.first() {
+      if row.len() != first.len() {
+        return Err(SolveError::parse(
+          number,
+          row.len().min(first.len()) + 1,
+          format!("expected {} columns, got {}", first.len(), row.len()),
+        ));
+      }
+    }
+    rows.push(row);
+  }
+  Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+  use std::fs;
+  use std::path::PathBuf;
+
+  use super::{comma_separated, digits, find_inputs, grid, ints, lines, normalize, split};
+  use crate::error::SolveError;
+
+  #[test]
+  fn finds_file_and_directory() {
+    let dir = env::temp_dir().join(format!("aoc2019-inputs-{}", std::process::id()));
+    fs::create_dir_all(dir.join("day07")).unwrap();
+    fs::write(dir.join("day07.txt"), "3,0,99").unwrap();
+    fs::write(dir.join("day07/bob.txt"), "3,0,99").unwrap();
+    fs::write(dir.join("day07/alice.txt"), "3,0,99").unwrap();
+    fs::write(dir.join("day07/notes.md"), "").unwrap();
+    fs::write(dir.join("day08.txt"), "0").unwrap();
+
+    let inputs = find_inputs(&dir, 7).unwrap();
+    let found: Vec<PathBuf> = inputs
+      .iter()
+      .map(|p| p.strip_prefix(&dir).unwrap().to_path_buf())
+      .collect();
+    let expected: Vec<PathBuf> = vec!["day07.txt", "day07/alice.txt", "day07/bob.txt"]
+      .into_iter()
+      .map(PathBuf::from)
+      .collect();
+    assert_eq!(found, expected);
+    assert_eq!(find_inputs(&dir, 8).unwrap().len(), 1);
+    assert!(find_inputs(&dir, 9).unwrap().is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn normalizes() {
+    assert_eq!(normalize("12 \r\n14\r\n\r\n"), "12\n14\n");
+    assert_eq!(normalize("\n\n"), "");
+    let found: Vec<(usize, &str)> = lines("a\r\n\r\nb  \n\n").collect();
+    assert_eq!(found, vec![(1, "a"), (2, ""), (3, "b")]);
+  }
+
+  #[test]
+  fn numbers() {
+    assert_eq!(ints::<u32>("12\r\n 14\n1969\n\n"), Ok(vec![12, 14, 1969]));
+    assert!(matches!(
+      ints::<u32>("12\n 1x4"),
+      Err(SolveError::Parse {
+        line: 2,
+        column: 2,
+        ..
+      })
+    ));
+    assert!(matches!(
+      comma_separated::<i64>("1,0, -1\n,99,\n"),
+      Err(SolveError::Parse {
+        line: 2,
+        column: 1,
+        ..
+      })
+    ));
+    assert_eq!(
+      comma_separated::<i64>("1,0, -1,\n99\n"),
+      Ok(vec![1, 0, -1, 99])
+    );
+    assert!(matches!(
+      comma_separated::<i64>("1,0,x"),
+      Err(SolveError::Parse {
+        line: 1,
+        column: 5,
+        ..
+      })
+    ));
+  }
+
+  #[test]
+  fn fields() {
+    let parse = |field: &str| match field {
+      "a" => Ok(1),
+      _ => Err(format!("bad field {:?}", field)),
+    };
+    assert_eq!(split(3, "a, a", ',', parse), Ok(vec![1, 1]));
+    assert_eq!(
+      split(3, "a, b", ',', parse),
+      Err(SolveError::parse(3, 4, "bad field \"b\""))
+    );
+  }
+
+  #[test]
+  fn digits_and_grids() {
+    assert_eq!(digits("0129\r\n"), Ok(vec![0, 1, 2, 9]));
+    assert_eq!(
+      digits("01\n2"),
+      Err(SolveError::parse(2, 1, "expected a single line"))
+    );
+    assert_eq!(
+      digits("01a"),
+      Err(SolveError::parse(1, 3, "'a' is not a digit"))
+    );
+
+    assert_eq!(
+      grid(".#\r\n#.\r\n"),
+      Ok(vec![vec!['.', '#'], vec!['#', '.']])
+    );
+    assert_eq!(
+      grid(".#\n#\n"),
+      Err(SolveError::parse(2, 2, "expected 2 columns, got 1"))
+    );
+  }
+}
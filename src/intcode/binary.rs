@@ -0,0 +1,235 @@
+//! Compact binary encoding of Intcode programs.
+//!
+//! Layout: the `MAGIC` bytes, a `VERSION` byte, the number of cells as an
+//! unsigned LEB128 varint and then every cell zigzag-encoded as a varint.
+//! Small values (most opcodes and addresses) take a single byte.
+
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::parse_program;
+
+pub const MAGIC: [u8; 4] = *b"INTC";
+pub const VERSION: u8 = 1;
+
+/// A varint of an `i64` never needs more than 10 bytes.
+const MAX_VARINT_LEN: usize = 10;
+
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+  BadMagic,
+  UnsupportedVersion(u8),
+  Truncated,
+  Overlong(usize),
+  TooLarge(usize),
+  TrailingBytes(usize),
+}
+
+impl fmt::Display for DecodeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      DecodeError::BadMagic => write!(f, "not a binary Intcode program"),
+      DecodeError::UnsupportedVersion(v) => write!(f, "unsupported format version {}", v),
+      DecodeError::Truncated => write!(f, "unexpected end of data"),
+      DecodeError::Overlong(pos) => write!(f, "varint at byte {} is too long", pos),
+      DecodeError::TooLarge(pos) => write!(f, "varint at byte {} does not fit in 64 bits", pos),
+      DecodeError::TrailingBytes(n) => write!(f, "{} unexpected trailing bytes", n),
+    }
+  }
+}
+
+impl error::Error for DecodeError {}
+
+impl From<DecodeError> for io::Error {
+  fn from(err: DecodeError) -> Self {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+  }
+}
+
+pub fn is_binary(data: &[u8]) -> bool {
+  data.starts_with(&MAGIC)
+}
+
+pub fn encode(program: &[i64]) -> Vec<u8> {
+  let mut out = Vec::with_capacity(MAGIC.len() + 1 + program.len() * 2);
+  out.extend_from_slice(&MAGIC);
+  out.push(VERSION);
+  write_varint(&mut out, program.len() as u64);
+  for &cell in program {
+    write_varint(&mut out, zigzag(cell));
+  }
+  out
+}
+
+pub fn decode(data: &[u8]) -> Result<Vec<i64>, DecodeError> {
+  if !is_binary(data) {
+    return Err(DecodeError::BadMagic);
+  }
+  let mut pos = MAGIC.len();
+  match data.get(pos) {
+    Some(&VERSION) => pos += 1,
+    Some(&v) => return Err(DecodeError::UnsupportedVersion(v)),
+    None => return Err(DecodeError::Truncated),
+  }
+
+  let len = read_varint(data, &mut pos)? as usize;
+  // every cell takes at least one byte, so don't trust `len` blindly
+  let mut program = Vec::with_capacity(len.min(data.len() - pos));
+  for _ in 0..len {
+    program.push(unzigzag(read_varint(data, &mut pos)?));
+  }
+
+  if pos < data.len() {
+    return Err(DecodeError::TrailingBytes(data.len() - pos));
+  }
+  Ok(program)
+}
+
+/// Loads a program from `path`, accepting either the binary or the
+/// comma-separated text format.
+pub fn load_program<P: AsRef<Path>>(path: P) -> io::Result<Vec<i64>> {
+  from_bytes(fs::read(path)?)
+}
+
+/// Reads a program in either format from `data`.
+pub fn from_bytes(data: Vec<u8>) -> io::Result<Vec<i64>> {
+  if is_binary(&data) {
+    Ok(decode(&data)?)
+  } else {
+    let invalid = |e| io::Error::new(io::ErrorKind::InvalidData, e);
+    let text = String::from_utf8(data).map_err(|e| invalid(e.to_string()))?;
+    parse_program(text.trim()).map_err(invalid)
+  }
+}
+
+/// Saves a program to `path` in the binary format.
+pub fn save_program<P: AsRef<Path>>(path: P, program: &[i64]) -> io::Result<()> {
+  fs::write(path, encode(program))
+}
+
+fn zigzag(value: i64) -> u64 {
+  ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+  ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+  while value >= 0x80 {
+    out.push((value as u8) | 0x80);
+    value >>= 7;
+  }
+  out.push(value as u8);
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> Result<u64, DecodeError> {
+  let start = *pos;
+  let mut value: u64 = 0;
+  for i in 0..MAX_VARINT_LEN {
+    let byte = *data.get(*pos).ok_or(DecodeError::Truncated)?;
+    *pos += 1;
+    // The last byte only has room for the top bit of a u64
+    if i == MAX_VARINT_LEN - 1 && byte & 0x7f > 1 {
+      return Err(DecodeError::TooLarge(start));
+    }
+    value |= u64::from(byte & 0x7f) << (7 * i);
+    if byte & 0x80 == 0 {
+      return Ok(value);
+    }
+  }
+  Err(DecodeError::Overlong(start))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use std::io;
+
+  use super::{decode, encode, from_bytes, zigzag, DecodeError, MAGIC, VERSION};
+  use crate::intcode::parse_program;
+
+  fn round_trip(path: &str) {
+    let text = fs::read_to_string(path).unwrap();
+    let program = parse_program(text.trim()).unwrap();
+    assert!(!program.is_empty());
+
+    let encoded = encode(&program);
+    assert!(encoded.len() < text.len());
+    assert_eq!(decode(&encoded).unwrap(), program);
+  }
+
+  #[test]
+  fn round_trip_day02() {
+    round_trip("inputs/day02.txt");
+  }
+
+  #[test]
+  fn round_trip_day05() {
+    round_trip("inputs/day05.txt");
+  }
+
+  #[test]
+  fn round_trip_day09() {
+    round_trip("inputs/day09.txt");
+  }
+
+  #[test]
+  fn round_trip_day11() {
+    round_trip("inputs/day11.txt");
+  }
+
+  #[test]
+  fn round_trip_extremes() {
+    let program = vec![0, -1, 1, i64::MIN, i64::MAX, 1_125_899_906_842_624];
+    assert_eq!(decode(&encode(&program)).unwrap(), program);
+  }
+
+  #[test]
+  fn zigzag_small_values() {
+    assert_eq!(zigzag(0), 0);
+    assert_eq!(zigzag(-1), 1);
+    assert_eq!(zigzag(1), 2);
+    assert_eq!(zigzag(-2), 3);
+  }
+
+  #[test]
+  fn decode_errors() {
+    assert_eq!(decode(b"1,0,0,0,99"), Err(DecodeError::BadMagic));
+
+    let mut data = MAGIC.to_vec();
+    data.push(42);
+    assert_eq!(decode(&data), Err(DecodeError::UnsupportedVersion(42)));
+
+    let mut data = encode(&[1, 0, 0, 0, 99]);
+    data.pop();
+    assert_eq!(decode(&data), Err(DecodeError::Truncated));
+
+    let mut data = encode(&[99]);
+    data.push(0);
+    assert_eq!(decode(&data), Err(DecodeError::TrailingBytes(1)));
+
+    // One cell of u64::MAX, then one with a bit past the 64th
+    let mut data = MAGIC.to_vec();
+    data.extend_from_slice(&[VERSION, 1]);
+    data.extend_from_slice(&[0xff; 9]);
+    data.push(0x01);
+    assert_eq!(decode(&data), Ok(vec![i64::MIN]));
+    *data.last_mut().unwrap() = 0x02;
+    assert_eq!(decode(&data), Err(DecodeError::TooLarge(6)));
+  }
+
+  #[test]
+  fn text_with_bad_cells_is_invalid() {
+    assert_eq!(
+      from_bytes(b"1,0,0,0,99\n".to_vec()).unwrap(),
+      vec![1, 0, 0, 0, 99]
+    );
+    let err = from_bytes(b"1,0,zero,0,99".to_vec()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+  }
+}
@@ -0,0 +1,114 @@
+use std::error;
+use std::fmt;
+
+/// Everything that can go wrong while running an Intcode program.
+/// Values are stored pre-formatted so the error does not depend on the
+/// VM's word type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IntcodeError {
+  AlreadyHalted,
+  TooManyIterations(u32),
+  NullInstruction(usize),
+  UnknownOpcode {
+    ipr: usize,
+    opcode: String,
+  },
+  UnknownMode {
+    ipr: usize,
+    mode: u8,
+  },
+  ImmediateWrite {
+    ipr: usize,
+    instruction: String,
+  },
+  AddressOutOfRange {
+    ipr: usize,
+    value: String,
+  },
+  InvalidOpcode {
+    opcode: u8,
+    reason: &'static str,
+  },
+  /// A custom opcode returned a different number of values than it has
+  /// write parameters.
+  WrongWriteCount {
+    ipr: usize,
+    instruction: String,
+    expected: usize,
+    actual: usize,
+  },
+  /// `relative_base` is `None` for position mode accesses.
+  InvalidAddress {
+    ipr: usize,
+    relative_base: Option<i64>,
+    offset: i64,
+  },
+  Overflow {
+    ipr: usize,
+    op: &'static str,
+    lhs: String,
+    rhs: String,
+  },
+}
+
+impl fmt::Display for IntcodeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      IntcodeError::AlreadyHalted => write!(f, "Program has already halted!"),
+      IntcodeError::TooManyIterations(n) => write!(f, "Ran more than {} instructions!", n),
+      IntcodeError::NullInstruction(ipr) => {
+        write!(f, "IPR={} points to null instruction!", ipr)
+      }
+      IntcodeError::UnknownOpcode { ipr, opcode } => {
+        write!(f, "Found unknown opcode {} at IPR={}", opcode, ipr)
+      }
+      IntcodeError::UnknownMode { ipr, mode } => {
+        write!(f, "Found unknown parameter mode {} at IPR={}", mode, ipr)
+      }
+      IntcodeError::ImmediateWrite { ipr, instruction } => write!(
+        f,
+        "{} output param must not be immediate (IPR={})",
+        instruction, ipr
+      ),
+      IntcodeError::AddressOutOfRange { ipr, value } => {
+        write!(f, "{} is not a valid address (IPR={})", value, ipr)
+      }
+      IntcodeError::InvalidAddress {
+        ipr,
+        relative_base: Some(base),
+        offset,
+      } => write!(
+        f,
+        "Relative base {} + offset {} is not a valid address (IPR={})",
+        base, offset, ipr
+      ),
+      IntcodeError::InvalidAddress {
+        ipr,
+        relative_base: None,
+        offset,
+      } => write!(
+        f,
+        "Position {} is not a valid address (IPR={})",
+        offset, ipr
+      ),
+      IntcodeError::InvalidOpcode { opcode, reason } => {
+        write!(f, "Invalid custom opcode {}: {}", opcode, reason)
+      }
+      IntcodeError::WrongWriteCount {
+        ipr,
+        instruction,
+        expected,
+        actual,
+      } => write!(
+        f,
+        "{} returned {} values to write instead of {} (IPR={})",
+        instruction, actual, expected, ipr
+      ),
+      IntcodeError::Overflow { ipr, op, lhs, rhs } => {
+        write!(f, "{} {} {} overflowed (IPR={})", lhs, op, rhs, ipr)
+      }
+    }
+  }
+}
+
+impl error::Error for IntcodeError {}
@@ -0,0 +1,298 @@
+//! Custom opcodes for experimenting with extended Intcode instruction sets.
+//!
+//! ```
+//! use aoc2019::intcode::{CustomOpcode, Intcode, Outcome};
+//!
+//! // SUB [a, b] -> out
+//! let sub = CustomOpcode::new("SUB", 3, &[3], |_, args: &[i64]| {
+//!   Ok(Outcome::Next(vec![args[0] - args[1]]))
+//! });
+//! let mut intcode = Intcode::new(vec![1150, 7, 3, 7, 4, 7, 99, 0]);
+//! intcode.opcodes.register(50, sub).unwrap();
+//! intcode.run();
+//! assert_eq!(intcode.outputs, vec![4]);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use super::{IntcodeError, Memory};
+
+/// Opcodes understood by the VM itself, which cannot be replaced.
+const BUILTIN_OPCODES: [u8; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 99];
+
+/// The most parameters an instruction word has room for: an `i64` has 19
+/// digits, two of which are the opcode and the rest parameter modes. The top
+/// digit can only go up to 9, which is still enough for every mode.
+pub const MAX_ARITY: usize = 17;
+
+/// What the VM does after executing a custom opcode.
+pub enum Outcome<W> {
+  /// Writes the values to the write parameters (in order) and continues
+  /// with the next instruction.
+  Next(Vec<W>),
+  /// Continues execution at the given address.
+  Jump(W),
+  /// Stops execution like a missing input does.
+  /// The next `run` continues after this instruction.
+  Pause,
+  Halt,
+}
+
+type Exec<W> = dyn Fn(&Memory<W>, &[W]) -> Result<Outcome<W>, IntcodeError> + Send + Sync;
+
+pub struct CustomOpcode<W> {
+  name: String,
+  arity: usize,
+  writes: Vec<usize>,
+  exec: Arc<Exec<W>>,
+}
+
+impl<W> CustomOpcode<W> {
+  /// Creates an opcode taking `arity` parameters.
+  /// `writes` lists the (1-based) parameters that are written to; `exec`
+  /// receives the values of all other parameters in order.
+  pub fn new<F>(name: &str, arity: usize, writes: &[usize], exec: F) -> Self
+  where
+    F: Fn(&Memory<W>, &[W]) -> Result<Outcome<W>, IntcodeError> + Send + Sync + 'static,
+  {
+    Self {
+      name: name.to_string(),
+      arity,
+      writes: writes.to_vec(),
+      exec: Arc::new(exec),
+    }
+  }
+
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn arity(&self) -> usize {
+    self.arity
+  }
+
+  /// Returns true if the (1-based) parameter `number` is written to.
+  pub fn writes(&self, number: usize) -> bool {
+    self.writes.contains(&number)
+  }
+
+  pub(super) fn num_writes(&self) -> usize {
+    self.writes.len()
+  }
+
+  pub(super) fn exec(&self, memory: &Memory<W>, args: &[W]) -> Result<Outcome<W>, IntcodeError> {
+    (self.exec)(memory, args)
+  }
+}
+
+impl<W> fmt::Debug for CustomOpcode<W> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("CustomOpcode")
+      .field("name", &self.name)
+      .field("arity", &self.arity)
+      .field("writes", &self.writes)
+      .finish()
+  }
+}
+
+/// The custom opcodes known to a VM.
+/// Cloning is cheap, so one registry can be shared between several VMs.
+pub struct OpcodeRegistry<W> {
+  opcodes: HashMap<u8, Arc<CustomOpcode<W>>>,
+}
+
+impl<W> OpcodeRegistry<W> {
+  pub fn new() -> Self {
+    Self {
+      opcodes: HashMap::new(),
+    }
+  }
+
+  /// Registers `op` under the two-digit opcode `code`.
+  /// Built-in opcodes cannot be replaced.
+  pub fn register(&mut self, code: u8, op: CustomOpcode<W>) -> Result<(), IntcodeError> {
+    let invalid = |reason| {
+      Err(IntcodeError::InvalidOpcode {
+        opcode: code,
+        reason,
+      })
+    };
+    if code >= 100 {
+      return invalid("opcodes only have two digits");
+    }
+    if BUILTIN_OPCODES.contains(&code) {
+      return invalid("opcode is built in");
+    }
+    if self.opcodes.contains_key(&code) {
+      return invalid("opcode is already registered");
+    }
+    if op.arity > MAX_ARITY {
+      return invalid("too many parameters to encode their modes");
+    }
+    if op.writes.iter().any(|&w| w == 0 || w > op.arity) {
+      return invalid("write position is not a parameter");
+    }
+    self.opcodes.insert(code, Arc::new(op));
+    Ok(())
+  }
+
+  pub fn get(&self, code: u8) -> Option<Arc<CustomOpcode<W>>> {
+    self.opcodes.get(&code).cloned()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.opcodes.is_empty()
+  }
+}
+
+impl<W> Default for OpcodeRegistry<W> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<W> Clone for OpcodeRegistry<W> {
+  fn clone(&self) -> Self {
+    Self {
+      opcodes: self.opcodes.clone(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::{CustomOpcode, OpcodeRegistry, Outcome, MAX_ARITY};
+  use crate::intcode::{Intcode, IntcodeError};
+
+  #[test]
+  fn print_op() {
+    let printed = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&printed);
+    let print = CustomOpcode::new("PRINT", 1, &[], move |_, args: &[i64]| {
+      sink.lock().unwrap().push(args[0]);
+      Ok(Outcome::Next(vec![]))
+    });
+
+    let mut intcode = Intcode::new(vec![1101, 3, 4, 9, 40, 9, 104, 1, 99, 0]);
+    intcode.opcodes.register(40, print).unwrap();
+    intcode.run();
+    assert_eq!(*printed.lock().unwrap(), vec![7]);
+    assert_eq!(intcode.outputs, vec![1]);
+  }
+
+  #[test]
+  fn break_op_pauses() {
+    let brk = CustomOpcode::new("BREAK", 0, &[], |_, _: &[i64]| Ok(Outcome::Pause));
+
+    let mut intcode = Intcode::new(vec![104, 1, 41, 104, 2, 99]);
+    intcode.opcodes.register(41, brk).unwrap();
+    intcode.run();
+    assert_eq!(intcode.outputs, vec![1]);
+    assert!(!intcode.has_halted);
+    intcode.run();
+    assert_eq!(intcode.outputs, vec![1, 2]);
+    assert!(intcode.has_halted);
+  }
+
+  #[test]
+  fn writes_and_jumps() {
+    // MIN [a, b] -> out, JMP target
+    let min = CustomOpcode::new("MIN", 3, &[3], |_, args: &[i64]| {
+      Ok(Outcome::Next(vec![args[0].min(args[1])]))
+    });
+    let jmp = CustomOpcode::new("JMP", 1, &[], |_, args: &[i64]| Ok(Outcome::Jump(args[0])));
+
+    let mut registry = OpcodeRegistry::new();
+    registry.register(42, min).unwrap();
+    registry.register(43, jmp).unwrap();
+
+    let mut intcode = Intcode::new(vec![1142, 8, 3, 11, 143, 8, 4, 0, 4, 11, 99, 0]);
+    intcode.opcodes = registry;
+    intcode.run();
+    assert_eq!(intcode.outputs, vec![3]);
+  }
+
+  #[test]
+  fn register_errors() {
+    let nop = || CustomOpcode::new("NOP", 0, &[], |_, _: &[i64]| Ok(Outcome::Next(vec![])));
+    let mut registry = OpcodeRegistry::new();
+    assert!(registry.register(1, nop()).is_err());
+    assert!(registry.register(99, nop()).is_err());
+    assert!(registry.register(100, nop()).is_err());
+    assert!(registry.register(50, nop()).is_ok());
+    assert_eq!(
+      registry.register(50, nop()),
+      Err(IntcodeError::InvalidOpcode {
+        opcode: 50,
+        reason: "opcode is already registered",
+      })
+    );
+
+    let bad = CustomOpcode::new("BAD", 1, &[2], |_, _: &[i64]| Ok(Outcome::Next(vec![])));
+    assert!(registry.register(51, bad).is_err());
+
+    let wide = |arity| CustomOpcode::new("WIDE", arity, &[], |_, _: &[i64]| Ok(Outcome::Halt));
+    assert!(registry.register(52, wide(MAX_ARITY)).is_ok());
+    assert_eq!(
+      registry.register(53, wide(MAX_ARITY + 1)),
+      Err(IntcodeError::InvalidOpcode {
+        opcode: 53,
+        reason: "too many parameters to encode their modes",
+      })
+    );
+  }
+
+  #[test]
+  fn last_parameter_mode_fits() {
+    let last = Arc::new(Mutex::new(None));
+    let sink = Arc::clone(&last);
+    let wide = CustomOpcode::new("WIDE", MAX_ARITY, &[], move |_, args: &[i64]| {
+      *sink.lock().unwrap() = args.last().copied();
+      Ok(Outcome::Next(vec![]))
+    });
+
+    // Every parameter reads address 0, except the last one, which is an
+    // immediate 7
+    let mut program = vec![0; MAX_ARITY + 2];
+    program[0] = 54 + 10_i64.pow(MAX_ARITY as u32 + 1);
+    program[MAX_ARITY] = 7;
+    program[MAX_ARITY + 1] = 99;
+    let mut intcode = Intcode::new(program);
+    intcode.opcodes.register(54, wide).unwrap();
+    intcode.try_run().unwrap();
+    assert_eq!(*last.lock().unwrap(), Some(7));
+  }
+
+  #[test]
+  fn runtime_errors_name_the_opcode() {
+    let twice = CustomOpcode::new("TWICE", 1, &[1], |_, _: &[i64]| {
+      Ok(Outcome::Next(vec![1, 2]))
+    });
+    let mut intcode = Intcode::new(vec![104, 0, 60, 0, 99]);
+    intcode.opcodes.register(60, twice).unwrap();
+    assert_eq!(
+      intcode.try_run(),
+      Err(IntcodeError::WrongWriteCount {
+        ipr: 2,
+        instruction: "TWICE".to_string(),
+        expected: 1,
+        actual: 2,
+      })
+    );
+
+    let set = CustomOpcode::new("SET", 1, &[1], |_, _: &[i64]| Ok(Outcome::Next(vec![7])));
+    let mut intcode = Intcode::new(vec![161, 0, 99]);
+    intcode.opcodes.register(61, set).unwrap();
+    assert_eq!(
+      intcode.try_run(),
+      Err(IntcodeError::ImmediateWrite {
+        ipr: 0,
+        instruction: "SET".to_string(),
+      })
+    );
+  }
+}
@@ -1,42 +1,66 @@
 use std::collections::HashMap;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::fmt;
+use std::sync::Arc;
 
-pub fn parse_program(program: &str) -> Vec<i64> {
+pub mod binary;
+mod error;
+mod extension;
+pub mod optimize;
+mod word;
+
+pub use binary::{load_program, save_program};
+pub use error::IntcodeError;
+pub use extension::{CustomOpcode, OpcodeRegistry, Outcome, MAX_ARITY};
+pub use word::Word;
+
+pub fn parse_program(program: &str) -> Result<Vec<i64>, String> {
+  parse_words(program)
+}
+
+/// Parses a program into any word type, e.g. `parse_words::<BigInt>(input)`.
+/// Fails on the first cell that is not a number, rather than skipping it
+/// and shifting every later address.
+pub fn parse_words<W: Word>(program: &str) -> Result<Vec<W>, String> {
   program
     .split(',')
-    .map(|x| x.parse::<i64>())
-    .filter_map(Result::ok)
+    .enumerate()
+    .map(|(i, cell)| {
+      let cell = cell.trim();
+      cell
+        .parse::<W>()
+        .map_err(|_| format!("cell {} is not a number: {:?}", i, cell))
+    })
     .collect()
 }
 
-#[derive(Clone, Copy, PartialEq)]
-enum Parameter {
-  Position(u64),
-  Immediate(i64),
+#[derive(Clone, PartialEq)]
+enum Parameter<W> {
+  Position(i64),
+  Immediate(W),
   Relative(i64),
 }
 
-impl Parameter {
-  fn create(mode: u8, value: i64) -> Parameter {
+impl<W: Word> Parameter<W> {
+  fn create(ipr: usize, mode: u8, value: W) -> Result<Parameter<W>, IntcodeError> {
+    let address = || {
+      value
+        .to_i64()
+        .ok_or_else(|| IntcodeError::AddressOutOfRange {
+          ipr,
+          value: value.to_string(),
+        })
+    };
     match mode {
-      0 => Parameter::Position(value as u64),
-      1 => Parameter::Immediate(value),
-      2 => Parameter::Relative(value),
-      _ => panic!("Found unknown parameter mode {} with value {}", mode, value),
-    }
-  }
-
-  fn get_value(&self, memory: &Memory) -> i64 {
-    match self {
-      Parameter::Position(p) => *memory.get(*p as usize),
-      Parameter::Immediate(v) => *v,
-      Parameter::Relative(o) => *memory.get((memory.relative_base + *o) as usize),
+      0 => Ok(Parameter::Position(address()?)),
+      1 => Ok(Parameter::Immediate(value)),
+      2 => Ok(Parameter::Relative(address()?)),
+      _ => Err(IntcodeError::UnknownMode { ipr, mode }),
     }
   }
 }
 
-impl fmt::Display for Parameter {
+impl<W: Word> fmt::Display for Parameter<W> {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
       Parameter::Position(p) => write!(f, "pos({})", p),
@@ -46,20 +70,21 @@ impl fmt::Display for Parameter {
   }
 }
 
-enum Instruction {
-  Add(Parameter, Parameter, Parameter),
-  Multiply(Parameter, Parameter, Parameter),
-  Input(Parameter),
-  Output(Parameter),
-  JumpIfTrue(Parameter, Parameter),
-  JumpIfFalse(Parameter, Parameter),
-  LessThan(Parameter, Parameter, Parameter),
-  Equal(Parameter, Parameter, Parameter),
-  RelativeBaseOffset(Parameter),
+enum Instruction<W> {
+  Add(Parameter<W>, Parameter<W>, Parameter<W>),
+  Multiply(Parameter<W>, Parameter<W>, Parameter<W>),
+  Input(Parameter<W>),
+  Output(Parameter<W>),
+  JumpIfTrue(Parameter<W>, Parameter<W>),
+  JumpIfFalse(Parameter<W>, Parameter<W>),
+  LessThan(Parameter<W>, Parameter<W>, Parameter<W>),
+  Equal(Parameter<W>, Parameter<W>, Parameter<W>),
+  RelativeBaseOffset(Parameter<W>),
   Halt,
+  Custom(Arc<CustomOpcode<W>>, Vec<Parameter<W>>),
 }
 
-impl fmt::Display for Instruction {
+impl<W: Word> fmt::Display for Instruction<W> {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
       Instruction::Add(p1, p2, out) => write!(f, "ADD [{}, {}] -> {}", p1, p2, out),
@@ -72,291 +97,447 @@ impl fmt::Display for Instruction {
       Instruction::Equal(p1, p2, out) => write!(f, "EQ [{}, {}] -> {}", p1, p2, out),
       Instruction::RelativeBaseOffset(p) => write!(f, "RBO {}", p),
       Instruction::Halt => write!(f, "HALT"),
+      Instruction::Custom(op, params) => {
+        let params: Vec<String> = params.iter().map(Parameter::to_string).collect();
+        write!(f, "{} [{}]", op.name(), params.join(", "))
+      }
     }
   }
 }
 
-pub struct Memory {
-  pub program: Vec<i64>,
-  additional: HashMap<usize, i64>,
+/// How memory treats an address that is negative or overflows `i64`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AddressPolicy {
+  /// Reject the access with `IntcodeError::InvalidAddress`.
+  #[default]
+  Error,
+  /// Negative addresses count back from the end of the loaded program.
+  Wrap,
+  /// Negative addresses are backed by sparse memory, just like addresses
+  /// past the end of the program.
+  Permissive,
+}
+
+pub struct Memory<W = i64> {
+  pub program: Vec<W>,
+  pub policy: AddressPolicy,
+  additional: HashMap<i64, W>,
   relative_base: i64,
+  zero: W,
 }
 
-impl Memory {
-  fn new(program: Vec<i64>) -> Self {
+impl<W: Word> Memory<W> {
+  fn new(program: Vec<W>) -> Self {
     Self {
       program,
+      policy: AddressPolicy::default(),
       additional: HashMap::new(),
       relative_base: 0,
+      zero: W::from_i64(0),
     }
   }
 
   fn exists(&self, address: usize) -> bool {
-    address < self.program.len() || self.additional.contains_key(&address)
+    address < self.program.len()
+      || i64::try_from(address).is_ok_and(|a| self.additional.contains_key(&a))
   }
 
-  /// Gets the value at the specified `address`.
-  /// Since Intcode is ok with non-existent addresses, it returns 0 in such cases.
-  pub fn get(&self, address: usize) -> &i64 {
-    self
-      .program
-      .get(address)
+  /// Turns `offset` (or `relative_base + offset` for relative accesses) into
+  /// an address according to the `policy`.
+  /// Every memory access of the VM goes through here.
+  fn resolve(&self, relative: bool, offset: i64) -> Option<i64> {
+    let base = if relative { self.relative_base } else { 0 };
+    let address = base.checked_add(offset)?;
+    if address >= 0 {
+      return Some(address);
+    }
+    match self.policy {
+      AddressPolicy::Error => None,
+      AddressPolicy::Wrap if self.program.is_empty() => None,
+      AddressPolicy::Wrap => Some(address.rem_euclid(self.program.len() as i64)),
+      AddressPolicy::Permissive => Some(address),
+    }
+  }
+
+  fn read(&self, address: i64) -> &W {
+    usize::try_from(address)
+      .ok()
+      .and_then(|a| self.program.get(a))
       .or_else(|| self.additional.get(&address))
-      .unwrap_or(&0)
+      .unwrap_or(&self.zero)
   }
 
-  /// Gets the value at the address `relative_base + offset`.
+  /// Gets the value at the specified `address`.
   /// Since Intcode is ok with non-existent addresses, it returns 0 in such cases.
-  pub fn get_rel(&self, offset: i64) -> &i64 {
-    self.get((self.relative_base + offset) as usize)
+  pub fn get(&self, address: usize) -> &W {
+    i64::try_from(address).map_or(&self.zero, |a| self.read(a))
   }
 
-  fn set(&mut self, address: usize, value: i64) {
-    if address < self.program.len() {
-      self.program[address] = value;
-    } else {
-      self.additional.insert(address, value);
-    }
+  /// Gets the value at the address `relative_base + offset`.
+  /// Since Intcode is ok with non-existent addresses, it returns 0 in such cases,
+  /// but `None` if the address is invalid under the `policy`.
+  pub fn get_rel(&self, offset: i64) -> Option<&W> {
+    self.resolve(true, offset).map(|a| self.read(a))
   }
 
-  fn set_rel(&mut self, offset: i64, value: i64) {
-    self.set((self.relative_base + offset) as usize, value);
+  fn set(&mut self, address: i64, value: W) {
+    match usize::try_from(address) {
+      Ok(a) if a < self.program.len() => self.program[a] = value,
+      _ => {
+        self.additional.insert(address, value);
+      }
+    }
   }
 }
 
-pub struct Intcode {
-  pub memory: Memory,
-  pub debug: bool,
-  pub inputs: Vec<i64>,
+/// An Intcode VM computing with words of type `W`.
+///
+/// The default `i64` words report overflowing arithmetic as
+/// `IntcodeError::Overflow`; use `i128` or `BigInt` for programs that need
+/// larger numbers.
+pub struct Intcode<W = i64> {
+  pub memory: Memory<W>,
+  pub inputs: Vec<W>,
   next_input: usize,
-  pub outputs: Vec<i64>,
+  pub outputs: Vec<W>,
   ipr: usize,
   iters: u32,
   pub has_halted: bool,
+  pub opcodes: OpcodeRegistry<W>,
 }
 
 const MAX_ITERS: u32 = 1_000_000;
 
-impl Intcode {
+impl Intcode<i64> {
   pub fn new(program: Vec<i64>) -> Self {
+    Self::from_program(program)
+  }
+}
+
+impl<W: Word> Intcode<W> {
+  pub fn from_program(program: Vec<W>) -> Self {
     Intcode {
       memory: Memory::new(program),
-      debug: false,
       inputs: Vec::new(),
       next_input: 0,
       outputs: Vec::new(),
       ipr: 0,
       iters: 0,
       has_halted: false,
+      opcodes: OpcodeRegistry::new(),
     }
   }
 
-  fn get_param(&self, opcode: u64, number: usize) -> Parameter {
-    let value = *self.memory.get(self.ipr + number);
-    Parameter::create(get_mode(opcode, number), value)
+  fn get_param(&self, opcode: u64, number: usize) -> Result<Parameter<W>, IntcodeError> {
+    let value = self.memory.get(self.ipr + number).clone();
+    Parameter::create(self.ipr, get_mode(opcode, number), value)
   }
 
-  fn get_instruction(&self) -> Instruction {
-    let opcode = *self.memory.get(self.ipr) as u64;
-    match opcode % 100 {
+  fn get_instruction(&self) -> Result<Instruction<W>, IntcodeError> {
+    let word = self.memory.get(self.ipr);
+    let opcode = word
+      .to_i64()
+      .filter(|&op| op >= 0)
+      .ok_or_else(|| IntcodeError::UnknownOpcode {
+        ipr: self.ipr,
+        opcode: word.to_string(),
+      })? as u64;
+    let instruction = match opcode % 100 {
       1 => {
-        let param_1 = self.get_param(opcode, 1);
-        let param_2 = self.get_param(opcode, 2);
-        let param_3 = self.get_param(opcode, 3);
+        let param_1 = self.get_param(opcode, 1)?;
+        let param_2 = self.get_param(opcode, 2)?;
+        let param_3 = self.get_param(opcode, 3)?;
         Instruction::Add(param_1, param_2, param_3)
       }
       2 => {
-        let param_1 = self.get_param(opcode, 1);
-        let param_2 = self.get_param(opcode, 2);
-        let param_3 = self.get_param(opcode, 3);
+        let param_1 = self.get_param(opcode, 1)?;
+        let param_2 = self.get_param(opcode, 2)?;
+        let param_3 = self.get_param(opcode, 3)?;
         Instruction::Multiply(param_1, param_2, param_3)
       }
       3 => {
-        let param_1 = self.get_param(opcode, 1);
+        let param_1 = self.get_param(opcode, 1)?;
         Instruction::Input(param_1)
       }
       4 => {
-        let param_1 = self.get_param(opcode, 1);
+        let param_1 = self.get_param(opcode, 1)?;
         Instruction::Output(param_1)
       }
       5 => {
-        let param_1 = self.get_param(opcode, 1);
-        let param_2 = self.get_param(opcode, 2);
+        let param_1 = self.get_param(opcode, 1)?;
+        let param_2 = self.get_param(opcode, 2)?;
         Instruction::JumpIfTrue(param_1, param_2)
       }
       6 => {
-        let param_1 = self.get_param(opcode, 1);
-        let param_2 = self.get_param(opcode, 2);
+        let param_1 = self.get_param(opcode, 1)?;
+        let param_2 = self.get_param(opcode, 2)?;
         Instruction::JumpIfFalse(param_1, param_2)
       }
       7 => {
-        let param_1 = self.get_param(opcode, 1);
-        let param_2 = self.get_param(opcode, 2);
-        let param_3 = self.get_param(opcode, 3);
+        let param_1 = self.get_param(opcode, 1)?;
+        let param_2 = self.get_param(opcode, 2)?;
+        let param_3 = self.get_param(opcode, 3)?;
         Instruction::LessThan(param_1, param_2, param_3)
       }
       8 => {
-        let param_1 = self.get_param(opcode, 1);
-        let param_2 = self.get_param(opcode, 2);
-        let param_3 = self.get_param(opcode, 3);
+        let param_1 = self.get_param(opcode, 1)?;
+        let param_2 = self.get_param(opcode, 2)?;
+        let param_3 = self.get_param(opcode, 3)?;
         Instruction::Equal(param_1, param_2, param_3)
       }
       9 => {
-        let param_1 = self.get_param(opcode, 1);
+        let param_1 = self.get_param(opcode, 1)?;
         Instruction::RelativeBaseOffset(param_1)
       }
       99 => Instruction::Halt,
-      _ => panic!("Found unknown opcode {}", opcode),
+      code => {
+        let op = self
+          .opcodes
+          .get(code as u8)
+          .ok_or_else(|| IntcodeError::UnknownOpcode {
+            ipr: self.ipr,
+            opcode: opcode.to_string(),
+          })?;
+        let params = (1..=op.arity())
+          .map(|number| self.get_param(opcode, number))
+          .collect::<Result<_, _>>()?;
+        Instruction::Custom(op, params)
+      }
+    };
+    Ok(instruction)
+  }
+
+  fn resolve(&self, relative: bool, offset: i64) -> Result<i64, IntcodeError> {
+    self
+      .memory
+      .resolve(relative, offset)
+      .ok_or(IntcodeError::InvalidAddress {
+        ipr: self.ipr,
+        relative_base: if relative {
+          Some(self.memory.relative_base)
+        } else {
+          None
+        },
+        offset,
+      })
+  }
+
+  /// Reads the value of an input parameter.
+  fn read(&self, param: &Parameter<W>) -> Result<W, IntcodeError> {
+    let address = match param {
+      Parameter::Position(pos) => self.resolve(false, *pos)?,
+      Parameter::Immediate(val) => return Ok(val.clone()),
+      Parameter::Relative(off) => self.resolve(true, *off)?,
+    };
+    Ok(self.memory.read(address).clone())
+  }
+
+  /// Writes `value` to the location of an output parameter.
+  fn write(&mut self, out: Parameter<W>, value: W, instruction: &str) -> Result<(), IntcodeError> {
+    let address = match out {
+      Parameter::Position(pos) => self.resolve(false, pos)?,
+      Parameter::Relative(off) => self.resolve(true, off)?,
+      Parameter::Immediate(_) => {
+        return Err(IntcodeError::ImmediateWrite {
+          ipr: self.ipr,
+          instruction: instruction.to_string(),
+        })
+      }
+    };
+    self.memory.set(address, value);
+    Ok(())
+  }
+
+  fn jump_target(&self, value: W) -> Result<usize, IntcodeError> {
+    let target = value
+      .to_i64()
+      .ok_or_else(|| IntcodeError::AddressOutOfRange {
+        ipr: self.ipr,
+        value: value.to_string(),
+      })?;
+    let address = self.resolve(false, target)?;
+    // even permissive memory cannot execute at a negative address
+    usize::try_from(address).map_err(|_| IntcodeError::InvalidAddress {
+      ipr: self.ipr,
+      relative_base: None,
+      offset: target,
+    })
+  }
+
+  fn overflow(&self, op: &'static str, lhs: &W, rhs: &W) -> IntcodeError {
+    IntcodeError::Overflow {
+      ipr: self.ipr,
+      op,
+      lhs: lhs.to_string(),
+      rhs: rhs.to_string(),
     }
   }
 
+  /// Runs the program until it halts or waits for input.
+  /// Panics on any error, see `try_run` for the fallible version.
   pub fn run(&mut self) {
+    if let Err(err) = self.try_run() {
+      panic!("{}", err);
+    }
+  }
+
+  /// Runs the program until it halts or waits for input.
+  pub fn try_run(&mut self) -> Result<(), IntcodeError> {
     if self.has_halted {
-      panic!("Program has already halted!");
+      return Err(IntcodeError::AlreadyHalted);
     }
 
     while self.memory.exists(self.ipr) {
       self.iters += 1;
       if self.iters > MAX_ITERS {
-        panic!("Ran too many times!");
+        return Err(IntcodeError::TooManyIterations(MAX_ITERS));
       }
 
-      let instruction = self.get_instruction();
-      if self.debug {
-        println!("{}", instruction);
-      }
+      let instruction = self.get_instruction()?;
+      trace!("{:>5}: {}", self.ipr, instruction);
       match instruction {
         Instruction::Add(p1, p2, out) => {
-          let val1 = p1.get_value(&self.memory);
-          let val2 = p2.get_value(&self.memory);
-          match out {
-            Parameter::Position(pos) => {
-              self.memory.set(pos as usize, val1 + val2);
-            }
-            Parameter::Relative(off) => {
-              self.memory.set_rel(off, val1 + val2);
-            }
-            _ => panic!("Add output param must always be position!"),
-          };
+          let val1 = self.read(&p1)?;
+          let val2 = self.read(&p2)?;
+          let sum = val1
+            .checked_add(&val2)
+            .ok_or_else(|| self.overflow("+", &val1, &val2))?;
+          self.write(out, sum, "Add")?;
 
           self.ipr += 4;
         }
         Instruction::Multiply(p1, p2, out) => {
-          let val1 = p1.get_value(&self.memory);
-          let val2 = p2.get_value(&self.memory);
-          match out {
-            Parameter::Position(pos) => {
-              self.memory.set(pos as usize, val1 * val2);
-            }
-            Parameter::Relative(off) => {
-              self.memory.set_rel(off, val1 * val2);
-            }
-            _ => panic!("Multiply output param must always be position!"),
-          };
+          let val1 = self.read(&p1)?;
+          let val2 = self.read(&p2)?;
+          let product = val1
+            .checked_mul(&val2)
+            .ok_or_else(|| self.overflow("*", &val1, &val2))?;
+          self.write(out, product, "Multiply")?;
 
           self.ipr += 4;
         }
         Instruction::Input(loc) => {
-          let inp: i64;
+          let inp: W;
           match self.inputs.get(self.next_input) {
             Some(val) => {
-              inp = *val;
+              inp = val.clone();
               self.next_input += 1;
             }
             None => {
-              if self.debug {
-                println!("No input available, pausing execution...");
-              }
-              return;
-            }
-          };
-          match loc {
-            Parameter::Position(pos) => {
-              self.memory.set(pos as usize, inp);
-            }
-            Parameter::Relative(off) => {
-              self.memory.set_rel(off, inp);
+              trace!("No input available, pausing execution...");
+              return Ok(());
             }
-            _ => panic!("Input param must always be position!"),
           };
+          self.write(loc, inp, "Input")?;
 
           self.ipr += 2;
         }
         Instruction::Output(p) => {
-          let output = p.get_value(&self.memory);
+          let output = self.read(&p)?;
+          debug!("Output: {}", output);
           self.outputs.push(output);
-          if self.debug {
-            println!("Output: {}", output);
-          }
 
           self.ipr += 2;
         }
         Instruction::JumpIfTrue(param, value) => {
-          let should_jump = param.get_value(&self.memory) != 0;
+          let should_jump = !self.read(&param)?.is_zero();
           if should_jump {
-            self.ipr = value.get_value(&self.memory) as usize;
+            self.ipr = self.jump_target(self.read(&value)?)?;
           } else {
             self.ipr += 3;
           }
         }
         Instruction::JumpIfFalse(param, value) => {
-          let should_jump = param.get_value(&self.memory) == 0;
+          let should_jump = self.read(&param)?.is_zero();
           if should_jump {
-            self.ipr = value.get_value(&self.memory) as usize;
+            self.ipr = self.jump_target(self.read(&value)?)?;
           } else {
             self.ipr += 3;
           }
         }
         Instruction::LessThan(p1, p2, out) => {
-          let val1 = p1.get_value(&self.memory);
-          let val2 = p2.get_value(&self.memory);
-          let output = if val1 < val2 { 1 } else { 0 };
-          match out {
-            Parameter::Position(pos) => {
-              self.memory.set(pos as usize, output);
-            }
-            Parameter::Relative(off) => {
-              self.memory.set_rel(off, output);
-            }
-            _ => panic!("LessThan output param must always be position!"),
-          };
+          let val1 = self.read(&p1)?;
+          let val2 = self.read(&p2)?;
+          let output = W::from_i64(if val1 < val2 { 1 } else { 0 });
+          self.write(out, output, "LessThan")?;
 
           self.ipr += 4;
         }
         Instruction::Equal(p1, p2, out) => {
-          let val1 = p1.get_value(&self.memory);
-          let val2 = p2.get_value(&self.memory);
-          let output = if val1 == val2 { 1 } else { 0 };
-          match out {
-            Parameter::Position(pos) => {
-              self.memory.set(pos as usize, output);
-            }
-            Parameter::Relative(off) => {
-              self.memory.set_rel(off, output);
-            }
-            _ => panic!("LessThan output param must always be position!"),
-          };
+          let val1 = self.read(&p1)?;
+          let val2 = self.read(&p2)?;
+          let output = W::from_i64(if val1 == val2 { 1 } else { 0 });
+          self.write(out, output, "Equal")?;
 
           self.ipr += 4;
         }
         Instruction::RelativeBaseOffset(p) => {
-          let change: i64 = match p {
-            Parameter::Position(pos) => *self.memory.get(pos as usize),
-            Parameter::Immediate(val) => val,
-            Parameter::Relative(off) => *self.memory.get_rel(off),
-          };
-          self.memory.relative_base += change;
+          let change = self.read(&p)?;
+          let change = change
+            .to_i64()
+            .ok_or_else(|| IntcodeError::AddressOutOfRange {
+              ipr: self.ipr,
+              value: change.to_string(),
+            })?;
+          let base = self.memory.relative_base;
+          self.memory.relative_base =
+            base
+              .checked_add(change)
+              .ok_or(IntcodeError::InvalidAddress {
+                ipr: self.ipr,
+                relative_base: Some(base),
+                offset: change,
+              })?;
 
           self.ipr += 2;
         }
         Instruction::Halt => {
           self.has_halted = true;
-          return;
+          return Ok(());
+        }
+        Instruction::Custom(op, params) => {
+          let mut args = Vec::with_capacity(params.len());
+          let mut outs = Vec::new();
+          for (i, param) in params.into_iter().enumerate() {
+            if op.writes(i + 1) {
+              outs.push(param);
+            } else {
+              args.push(self.read(&param)?);
+            }
+          }
+
+          match op.exec(&self.memory, &args)? {
+            Outcome::Next(values) => {
+              if values.len() != op.num_writes() {
+                return Err(IntcodeError::WrongWriteCount {
+                  ipr: self.ipr,
+                  instruction: op.name().to_string(),
+                  expected: op.num_writes(),
+                  actual: values.len(),
+                });
+              }
+              for (out, value) in outs.into_iter().zip(values) {
+                self.write(out, value, op.name())?;
+              }
+              self.ipr += 1 + op.arity();
+            }
+            Outcome::Jump(target) => {
+              self.ipr = self.jump_target(target)?;
+            }
+            Outcome::Pause => {
+              self.ipr += 1 + op.arity();
+              return Ok(());
+            }
+            Outcome::Halt => {
+              self.has_halted = true;
+              return Ok(());
+            }
+          }
         }
       };
     }
 
-    panic!("IPR={} points to null instruction!", self.ipr);
+    Err(IntcodeError::NullInstruction(self.ipr))
   }
 }
 
@@ -368,7 +549,9 @@ fn get_mode(opcode: u64, param_number: usize) -> u8 {
 
 #[cfg(test)]
 mod tests {
-  use super::get_mode;
+  use num_bigint::BigInt;
+
+  use super::{get_mode, parse_words, AddressPolicy, Intcode, IntcodeError};
 
   #[test]
   fn get_mode_1() {
@@ -381,4 +564,117 @@ mod tests {
     assert_eq!(get_mode(1101, 2), 1);
     assert_eq!(get_mode(1101, 3), 0);
   }
+
+  #[test]
+  fn parse_ignores_whitespace() {
+    assert_eq!(parse_words::<i64>("1, 0,\n99\n"), Ok(vec![1, 0, 99]));
+  }
+
+  #[test]
+  fn parse_rejects_bad_cells() {
+    assert_eq!(
+      parse_words::<i64>("1,0,x,99"),
+      Err("cell 2 is not a number: \"x\"".to_string())
+    );
+    assert!(parse_words::<i64>("1,,99").is_err());
+  }
+
+  #[test]
+  fn relative_base_overflow_is_an_error() {
+    let mut intcode = Intcode::new(vec![109, 9_223_372_036_854_775_807, 109, 1, 99]);
+    assert_eq!(
+      intcode.try_run(),
+      Err(IntcodeError::InvalidAddress {
+        ipr: 2,
+        relative_base: Some(9_223_372_036_854_775_807),
+        offset: 1,
+      })
+    );
+
+    let program = parse_words("109,9223372036854775808,99").unwrap();
+    let mut intcode = Intcode::<i128>::from_program(program);
+    assert_eq!(
+      intcode.try_run(),
+      Err(IntcodeError::AddressOutOfRange {
+        ipr: 0,
+        value: "9223372036854775808".to_string(),
+      })
+    );
+  }
+
+  #[test]
+  fn checked_overflow_is_an_error() {
+    let mut intcode = Intcode::new(vec![1102, 9_223_372_036_854_775_807, 2, 0, 99]);
+    assert_eq!(
+      intcode.try_run(),
+      Err(IntcodeError::Overflow {
+        ipr: 0,
+        op: "*",
+        lhs: "9223372036854775807".to_string(),
+        rhs: "2".to_string(),
+      })
+    );
+  }
+
+  #[test]
+  fn i128_words() {
+    let mut intcode =
+      Intcode::<i128>::from_program(parse_words("1102,9223372036854775807,2,7,4,7,99,0").unwrap());
+    intcode.run();
+    assert_eq!(intcode.outputs, vec![18_446_744_073_709_551_614]);
+  }
+
+  #[test]
+  fn bigint_words() {
+    let program = "1102,9223372036854775807,9223372036854775807,7,4,7,99,0";
+    let mut intcode = Intcode::<BigInt>::from_program(parse_words(program).unwrap());
+    intcode.run();
+    assert_eq!(
+      intcode.outputs[0].to_string(),
+      "85070591730234615847396907784232501249"
+    );
+  }
+
+  #[test]
+  fn negative_position_is_an_error() {
+    let mut intcode = Intcode::new(vec![4, -1, 99]);
+    assert_eq!(
+      intcode.try_run(),
+      Err(IntcodeError::InvalidAddress {
+        ipr: 0,
+        relative_base: None,
+        offset: -1,
+      })
+    );
+  }
+
+  #[test]
+  fn negative_relative_is_an_error() {
+    let mut intcode = Intcode::new(vec![109, -5, 204, 0, 99]);
+    assert_eq!(
+      intcode.try_run(),
+      Err(IntcodeError::InvalidAddress {
+        ipr: 2,
+        relative_base: Some(-5),
+        offset: 0,
+      })
+    );
+  }
+
+  #[test]
+  fn negative_relative_wraps() {
+    let mut intcode = Intcode::new(vec![109, -5, 204, 1, 99]);
+    intcode.memory.policy = AddressPolicy::Wrap;
+    intcode.run();
+    assert_eq!(intcode.outputs, vec![-5]);
+  }
+
+  #[test]
+  fn negative_relative_permissive() {
+    let mut intcode = Intcode::new(vec![109, -5, 204, 0, 21101, 7, 0, 0, 204, 0, 99]);
+    intcode.memory.policy = AddressPolicy::Permissive;
+    intcode.run();
+    assert_eq!(intcode.outputs, vec![0, 7]);
+    assert_eq!(intcode.memory.program[0], 109);
+  }
 }
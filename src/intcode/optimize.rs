@@ -0,0 +1,461 @@
+//! A peephole optimizer for Intcode programs.
+//!
+//! Code and data share one address space, so the optimizer never moves or
+//! deletes cells: every rewrite replaces an instruction with an equivalent
+//! one of the same length. Instructions are only found by following control
+//! flow from address 0, and any instruction with a cell that the program
+//! reads or writes through a position mode parameter is left untouched.
+//! Where a relative mode access lands, or where a jump to a target read from
+//! memory goes, is only known at run time, so programs that use relative
+//! mode or jump to a non-immediate target are left as they are.
+//!
+//! Rewrites:
+//! - arithmetic and comparisons on immediates are folded into a move of the
+//!   result (`ADD [imm(c), imm(0)]`),
+//! - `ADD x, 0`, `MUL x, 1` and `MUL x, 0` become plain moves,
+//! - jumps with a constant condition and jumps to the next instruction become
+//!   unconditional jumps or no-ops, and
+//! - jumps that land on a no-op or an unconditional jump are threaded
+//!   straight to their final destination, so the skipped instructions are
+//!   never executed.
+
+use std::collections::{BTreeMap, HashSet};
+
+use super::{Intcode, IntcodeError};
+
+const ADD: i64 = 1;
+const MULTIPLY: i64 = 2;
+const INPUT: i64 = 3;
+const OUTPUT: i64 = 4;
+const JUMP_IF_TRUE: i64 = 5;
+const JUMP_IF_FALSE: i64 = 6;
+const LESS_THAN: i64 = 7;
+const EQUAL: i64 = 8;
+const RELATIVE_BASE_OFFSET: i64 = 9;
+const HALT: i64 = 99;
+
+const POSITION: u8 = 0;
+const IMMEDIATE: u8 = 1;
+const RELATIVE: u8 = 2;
+
+/// Number of rewrites of each kind.
+#[derive(Debug, Default, PartialEq)]
+pub struct Stats {
+  pub folded: usize,
+  pub moves: usize,
+  pub jumps: usize,
+}
+
+impl Stats {
+  pub fn total(&self) -> usize {
+    self.folded + self.moves + self.jumps
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Op {
+  code: i64,
+  modes: Vec<u8>,
+  args: Vec<i64>,
+}
+
+/// The control flow effect of a (decoded) jump instruction.
+enum Jump {
+  Never,
+  Always,
+  Maybe,
+}
+
+impl Op {
+  fn decode(program: &[i64], addr: usize) -> Option<Op> {
+    let word = *program.get(addr)?;
+    if word < 0 {
+      return None;
+    }
+    let code = word % 100;
+    let arity = match code {
+      ADD | MULTIPLY | LESS_THAN | EQUAL => 3,
+      JUMP_IF_TRUE | JUMP_IF_FALSE => 2,
+      INPUT | OUTPUT | RELATIVE_BASE_OFFSET => 1,
+      HALT => 0,
+      _ => return None,
+    };
+    let mut modes = Vec::with_capacity(arity);
+    let mut rest = word / 100;
+    for _ in 0..arity {
+      let mode = (rest % 10) as u8;
+      if mode > 2 {
+        return None;
+      }
+      modes.push(mode);
+      rest /= 10;
+    }
+    if rest != 0 {
+      return None;
+    }
+    let args = program.get(addr + 1..addr + 1 + arity)?.to_vec();
+    Some(Op { code, modes, args })
+  }
+
+  fn encode(&self) -> Vec<i64> {
+    let mut word = self.code;
+    let mut place = 100;
+    for &mode in &self.modes {
+      word += place * i64::from(mode);
+      place *= 10;
+    }
+    let mut cells = vec![word];
+    cells.extend_from_slice(&self.args);
+    cells
+  }
+
+  fn len(&self) -> usize {
+    1 + self.args.len()
+  }
+
+  fn imm(&self, param: usize) -> Option<i64> {
+    if self.modes[param] == IMMEDIATE {
+      Some(self.args[param])
+    } else {
+      None
+    }
+  }
+
+  fn is_jump(&self) -> bool {
+    self.code == JUMP_IF_TRUE || self.code == JUMP_IF_FALSE
+  }
+
+  /// Only valid for jump instructions at `addr`.
+  fn jump(&self, addr: usize) -> Jump {
+    if self.imm(1) == Some((addr + self.len()) as i64) {
+      return Jump::Never;
+    }
+    match self.imm(0) {
+      Some(cond) if (cond != 0) == (self.code == JUMP_IF_TRUE) => Jump::Always,
+      Some(_) => Jump::Never,
+      None => Jump::Maybe,
+    }
+  }
+
+  /// A move of `param` (which keeps its mode) to the output parameter.
+  fn to_move(&self, param: usize) -> Op {
+    Op {
+      code: ADD,
+      modes: vec![self.modes[param], IMMEDIATE, self.modes[2]],
+      args: vec![self.args[param], 0, self.args[2]],
+    }
+  }
+
+  fn constant_move(&self, value: i64) -> Op {
+    Op {
+      code: ADD,
+      modes: vec![IMMEDIATE, IMMEDIATE, self.modes[2]],
+      args: vec![value, 0, self.args[2]],
+    }
+  }
+}
+
+/// Finds every instruction reachable from address 0 through fall-through
+/// and immediate jump targets.
+fn reachable(program: &[i64]) -> BTreeMap<usize, Op> {
+  let mut ops = BTreeMap::new();
+  let mut pending = vec![0];
+  while let Some(addr) = pending.pop() {
+    if ops.contains_key(&addr) {
+      continue;
+    }
+    let op = match Op::decode(program, addr) {
+      Some(op) => op,
+      None => continue,
+    };
+    let next = addr + op.len();
+    match op.code {
+      HALT => {}
+      JUMP_IF_TRUE | JUMP_IF_FALSE => {
+        if let Some(target) = op.imm(1) {
+          if target >= 0 {
+            pending.push(target as usize);
+          }
+        }
+        if let Jump::Never | Jump::Maybe = op.jump(addr) {
+          pending.push(next);
+        }
+      }
+      _ => pending.push(next),
+    }
+    ops.insert(addr, op);
+  }
+  ops
+}
+
+/// Returns the addresses of the instructions that are safe to rewrite:
+/// none of their cells is accessed as data and they don't overlap another
+/// instruction.
+fn rewritable(ops: &BTreeMap<usize, Op>) -> HashSet<usize> {
+  let mut data = HashSet::new();
+  for op in ops.values() {
+    for (&mode, &arg) in op.modes.iter().zip(&op.args) {
+      if mode == POSITION && arg >= 0 {
+        data.insert(arg as usize);
+      }
+    }
+  }
+
+  let mut safe = HashSet::new();
+  let mut prev_end = 0;
+  let mut prev_addr = None;
+  for (&addr, op) in ops {
+    let overlaps = addr < prev_end;
+    if overlaps {
+      if let Some(prev) = prev_addr {
+        safe.remove(&prev);
+      }
+    } else if (addr..addr + op.len()).all(|cell| !data.contains(&cell)) {
+      safe.insert(addr);
+    }
+    prev_end = prev_end.max(addr + op.len());
+    prev_addr = Some(addr);
+  }
+  safe
+}
+
+/// Rewrites a single instruction, returning `None` if nothing changes.
+fn simplify(addr: usize, op: &Op, stats: &mut Stats) -> Option<Op> {
+  match op.code {
+    ADD | MULTIPLY | LESS_THAN | EQUAL => {
+      if let (Some(a), Some(b)) = (op.imm(0), op.imm(1)) {
+        if op.code == ADD && b == 0 {
+          return None; // already a constant move
+        }
+        let value = match op.code {
+          ADD => a.checked_add(b)?,
+          MULTIPLY => a.checked_mul(b)?,
+          LESS_THAN => (a < b) as i64,
+          _ => (a == b) as i64,
+        };
+        stats.folded += 1;
+        return Some(op.constant_move(value));
+      }
+      let simpler = match (op.code, op.imm(0), op.imm(1)) {
+        (ADD, Some(0), _) => op.to_move(1),
+        (MULTIPLY, Some(1), _) => op.to_move(1),
+        (MULTIPLY, _, Some(1)) => op.to_move(0),
+        (MULTIPLY, Some(0), _) | (MULTIPLY, _, Some(0)) => op.constant_move(0),
+        _ => return None,
+      };
+      stats.moves += 1;
+      Some(simpler)
+    }
+    JUMP_IF_TRUE | JUMP_IF_FALSE => {
+      let canonical = match op.jump(addr) {
+        Jump::Never => Op {
+          code: JUMP_IF_FALSE,
+          modes: vec![IMMEDIATE, IMMEDIATE],
+          args: vec![1, (addr + op.len()) as i64],
+        },
+        Jump::Always => Op {
+          code: JUMP_IF_TRUE,
+          modes: vec![IMMEDIATE, op.modes[1]],
+          args: vec![1, op.args[1]],
+        },
+        Jump::Maybe => return None,
+      };
+      if canonical == *op {
+        return None;
+      }
+      stats.jumps += 1;
+      Some(canonical)
+    }
+    _ => None,
+  }
+}
+
+/// Follows no-ops and unconditional jumps starting at `target`.
+fn thread(mut target: usize, ops: &BTreeMap<usize, Op>, safe: &HashSet<usize>) -> usize {
+  let mut seen = HashSet::new();
+  while safe.contains(&target) && seen.insert(target) {
+    let op = &ops[&target];
+    if !op.is_jump() {
+      break;
+    }
+    target = match (op.jump(target), op.imm(1)) {
+      (Jump::Never, _) => target + op.len(),
+      (Jump::Always, Some(next)) if next >= 0 => next as usize,
+      _ => break,
+    };
+  }
+  target
+}
+
+/// Returns an equivalent program that executes fewer or cheaper
+/// instructions, along with what was rewritten.
+pub fn optimize(program: &[i64]) -> (Vec<i64>, Stats) {
+  let mut stats = Stats::default();
+  let mut ops = reachable(program);
+  if ops
+    .values()
+    .any(|op| op.modes.contains(&RELATIVE) || (op.is_jump() && op.imm(1).is_none()))
+  {
+    return (program.to_vec(), stats);
+  }
+  let safe = rewritable(&ops);
+
+  for (&addr, op) in ops.iter_mut() {
+    if safe.contains(&addr) {
+      if let Some(simpler) = simplify(addr, op, &mut stats) {
+        *op = simpler;
+      }
+    }
+  }
+
+  let mut threaded = Vec::new();
+  for (&addr, op) in &ops {
+    if !safe.contains(&addr) || !op.is_jump() {
+      continue;
+    }
+    if let (Jump::Always, Some(target)) | (Jump::Maybe, Some(target)) = (op.jump(addr), op.imm(1)) {
+      if target >= 0 {
+        let final_target = thread(target as usize, &ops, &safe);
+        if final_target != target as usize {
+          threaded.push((addr, final_target as i64));
+        }
+      }
+    }
+  }
+  for (addr, target) in threaded {
+    ops.get_mut(&addr).unwrap().args[1] = target;
+    stats.jumps += 1;
+  }
+
+  let mut optimized = program.to_vec();
+  for (&addr, op) in &ops {
+    if safe.contains(&addr) {
+      let cells = op.encode();
+      optimized[addr..addr + cells.len()].copy_from_slice(&cells);
+    }
+  }
+  (optimized, stats)
+}
+
+/// Runs both programs on the same `inputs` and checks that they produce the
+/// same outputs.
+pub fn verify(original: &[i64], optimized: &[i64], inputs: &[i64]) -> Result<bool, IntcodeError> {
+  let run = |program: &[i64]| -> Result<Vec<i64>, IntcodeError> {
+    let mut intcode = Intcode::new(program.to_vec());
+    intcode.inputs = inputs.to_vec();
+    intcode.try_run()?;
+    Ok(intcode.outputs)
+  };
+  Ok(run(original)? == run(optimized)?)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use super::{optimize, verify, Stats};
+  use crate::intcode::{parse_program, Intcode};
+
+  fn load(path: &str) -> Vec<i64> {
+    parse_program(fs::read_to_string(path).unwrap().trim()).unwrap()
+  }
+
+  #[test]
+  fn folds_immediates() {
+    let (optimized, stats) = optimize(&[1102, 6, 7, 9, 1107, 1, 2, 10, 99, 0, 0]);
+    assert_eq!(optimized, vec![1101, 42, 0, 9, 1101, 1, 0, 10, 99, 0, 0]);
+    assert_eq!(stats.folded, 2);
+  }
+
+  #[test]
+  fn collapses_moves() {
+    let program = vec![1001, 13, 0, 14, 101, 0, 13, 14, 102, 1, 13, 14, 99, 5, 0];
+    let (optimized, stats) = optimize(&program);
+    assert_eq!(
+      optimized,
+      vec![1001, 13, 0, 14, 1001, 13, 0, 14, 1001, 13, 0, 14, 99, 5, 0]
+    );
+    assert_eq!(stats.moves, 2);
+  }
+
+  #[test]
+  fn threads_jumps() {
+    // 0 jumps to a no-op at 5, which falls through to a jump to 13
+    let program = vec![
+      1105, 1, 5, 104, 8, 1006, 16, 8, 1105, 1, 13, 104, 9, 104, 7, 99, 0,
+    ];
+    let (optimized, stats) = optimize(&program);
+    assert_eq!(
+      optimized,
+      vec![1105, 1, 13, 104, 8, 1106, 1, 8, 1105, 1, 13, 104, 9, 104, 7, 99, 0]
+    );
+    assert_eq!(stats.jumps, 2);
+    assert!(verify(&program, &optimized, &[]).unwrap());
+  }
+
+  #[test]
+  fn keeps_data_cells() {
+    // the first instruction is read as data by the output instruction
+    let program = vec![1101, 2, 3, 7, 4, 1, 99, 0];
+    let (optimized, stats) = optimize(&program);
+    assert_eq!(optimized, program);
+    assert_eq!(stats, Stats::default());
+  }
+
+  #[test]
+  fn leaves_relative_mode_programs() {
+    // The relative write at 2 zeroes the first operand of the add at 6, so
+    // this prints 3, where a folded add would print 0
+    let program = vec![109, 3, 21101, 0, 0, 4, 1101, 2, 3, 14, 4, 14, 99, 0, 0];
+    let (optimized, stats) = optimize(&program);
+    assert_eq!(optimized, program);
+    assert_eq!(stats, Stats::default());
+  }
+
+  #[test]
+  fn leaves_programs_with_indirect_jumps() {
+    // The jump at 6 goes to 9 through cell 41, and the code there patches
+    // the add at 0, so this prints 5 then 12, where a folded add would
+    // print 5 then 15
+    let mut program = vec![
+      1101, 2, 3, 40, 4, 40, 105, 1, 41, 1101, 10, 0, 2, 1101, 20, 0, 41, 1105, 1, 0, 99,
+    ];
+    program.resize(42, 0);
+    program[41] = 9;
+    let (optimized, stats) = optimize(&program);
+    assert_eq!(optimized, program);
+    assert_eq!(stats, Stats::default());
+
+    let mut intcode = Intcode::new(program);
+    intcode.try_run().unwrap();
+    assert_eq!(intcode.outputs, vec![5, 12]);
+  }
+
+  #[test]
+  fn day05_equivalent() {
+    // The diagnostic program patches its own third instruction with the
+    // first input, so hardly anything can be proven safe to rewrite.
+    let program = load("inputs/day05.txt");
+    let (optimized, _) = optimize(&program);
+    assert!(verify(&program, &optimized, &[1]).unwrap());
+    assert!(verify(&program, &optimized, &[5]).unwrap());
+  }
+
+  #[test]
+  fn day09_unchanged() {
+    // BOOST uses relative mode throughout
+    let program = load("inputs/day09.txt");
+    let (optimized, stats) = optimize(&program);
+    assert_eq!(optimized, program);
+    assert_eq!(stats.total(), 0);
+  }
+
+  #[test]
+  fn day02_equivalent() {
+    let mut program = load("inputs/day02.txt");
+    program[1] = 12;
+    program[2] = 2;
+    let (optimized, _) = optimize(&program);
+    assert!(verify(&program, &optimized, &[]).unwrap());
+  }
+}
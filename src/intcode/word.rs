@@ -0,0 +1,118 @@
+//! Word types the Intcode VM can compute with.
+//!
+//! Intcode itself puts no bound on the size of its numbers, so the word type
+//! decides what happens when a program outgrows it: the fixed-width words
+//! report an overflow, while `BigInt` simply keeps growing.
+
+use std::fmt;
+use std::str::FromStr;
+
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+
+pub trait Word: Clone + fmt::Debug + fmt::Display + PartialEq + PartialOrd + FromStr {
+  fn from_i64(value: i64) -> Self;
+
+  /// Returns `None` if the value does not fit into an `i64`.
+  fn to_i64(&self) -> Option<i64>;
+
+  /// Returns `None` on overflow.
+  fn checked_add(&self, other: &Self) -> Option<Self>;
+
+  /// Returns `None` on overflow.
+  fn checked_mul(&self, other: &Self) -> Option<Self>;
+
+  fn is_zero(&self) -> bool {
+    *self == Self::from_i64(0)
+  }
+}
+
+impl Word for i64 {
+  fn from_i64(value: i64) -> Self {
+    value
+  }
+
+  fn to_i64(&self) -> Option<i64> {
+    Some(*self)
+  }
+
+  fn checked_add(&self, other: &Self) -> Option<Self> {
+    i64::checked_add(*self, *other)
+  }
+
+  fn checked_mul(&self, other: &Self) -> Option<Self> {
+    i64::checked_mul(*self, *other)
+  }
+}
+
+impl Word for i128 {
+  fn from_i64(value: i64) -> Self {
+    i128::from(value)
+  }
+
+  fn to_i64(&self) -> Option<i64> {
+    ToPrimitive::to_i64(self)
+  }
+
+  fn checked_add(&self, other: &Self) -> Option<Self> {
+    i128::checked_add(*self, *other)
+  }
+
+  fn checked_mul(&self, other: &Self) -> Option<Self> {
+    i128::checked_mul(*self, *other)
+  }
+}
+
+impl Word for BigInt {
+  fn from_i64(value: i64) -> Self {
+    BigInt::from(value)
+  }
+
+  fn to_i64(&self) -> Option<i64> {
+    ToPrimitive::to_i64(self)
+  }
+
+  fn checked_add(&self, other: &Self) -> Option<Self> {
+    Some(self + other)
+  }
+
+  fn checked_mul(&self, other: &Self) -> Option<Self> {
+    Some(self * other)
+  }
+
+  fn is_zero(&self) -> bool {
+    Zero::is_zero(self)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use num_bigint::BigInt;
+
+  use super::Word;
+
+  #[test]
+  fn i64_overflow() {
+    assert_eq!(Word::checked_mul(&i64::MAX, &2), None);
+    assert_eq!(Word::checked_add(&i64::MIN, &-1), None);
+  }
+
+  #[test]
+  fn i128_holds_i64_products() {
+    let max = <i128 as Word>::from_i64(i64::MAX);
+    let product = Word::checked_mul(&max, &max).unwrap();
+    assert_eq!(Word::to_i64(&product), None);
+    assert_eq!(Word::checked_mul(&product, &max), None);
+  }
+
+  #[test]
+  fn bigint_never_overflows() {
+    let max = BigInt::from_i64(i64::MAX);
+    let product = Word::checked_mul(&max, &max).unwrap();
+    let product = Word::checked_mul(&product, &max).unwrap();
+    assert_eq!(
+      product.to_string(),
+      "784637716923335095224261902710254454442933591094742482943"
+    );
+  }
+}
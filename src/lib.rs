@@ -1,13 +1,27 @@
-pub mod day01;
-pub mod day02;
-pub mod day03;
-pub mod day04;
-pub mod day05;
-pub mod day06;
-pub mod day07;
-pub mod day08;
-pub mod day09;
-pub mod day10;
-pub mod day11;
+// First, so its macros can be used in every other module
+#[macro_use]
+pub mod log;
+
+pub mod answers;
+pub mod bench;
+pub mod cache;
+pub mod cli;
+pub mod error;
+pub mod examples;
+pub mod fnv;
+pub mod input;
 pub mod intcode;
+pub mod output;
+pub mod pool;
 pub mod problem;
+#[macro_use]
+pub mod registry;
+pub mod report;
+pub mod rng;
+pub mod scaffold;
+pub mod timing;
+pub mod verify;
+pub mod watch;
+
+// `pub mod dayNN;` for every day, and `DAYS`.
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
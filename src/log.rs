@@ -0,0 +1,283 @@
+//! Leveled logging, filtered per target and written to one sink (stderr
+//! unless replaced with `set_sink`).
+//!
+//! A message's target is the module it is logged from, without the crate
+//! name, e.g. `day11` or `intcode`. Filters look like
+//! `intcode=trace,day11=debug`: a target's level applies to its submodules
+//! too, and an entry without a target sets the level for everything else
+//! (`warn` if not given).
+//!
+//! ```ignore
+//! debug!("painted {} panels", painted.len());
+//! ```
+
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Mutex, RwLock};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+  Off,
+  Error,
+  Warn,
+  Info,
+  Debug,
+  Trace,
+}
+
+impl Level {
+  fn as_str(self) -> &'static str {
+    match self {
+      Level::Off => "off",
+      Level::Error => "error",
+      Level::Warn => "warn",
+      Level::Info => "info",
+      Level::Debug => "debug",
+      Level::Trace => "trace",
+    }
+  }
+}
+
+impl FromStr for Level {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "off" => Ok(Level::Off),
+      "error" => Ok(Level::Error),
+      "warn" => Ok(Level::Warn),
+      "info" => Ok(Level::Info),
+      "debug" => Ok(Level::Debug),
+      "trace" => Ok(Level::Trace),
+      _ => Err(format!(
+        "Unknown log level {:?}, expected off, error, warn, info, debug or trace",
+        s
+      )),
+    }
+  }
+}
+
+/// Which messages to write: a level per target, and one for the rest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Filter {
+  default: Level,
+  targets: Vec<(String, Level)>,
+}
+
+impl Filter {
+  /// Everything at `level` and more severe.
+  pub const fn all(level: Level) -> Self {
+    Self {
+      default: level,
+      targets: Vec::new(),
+    }
+  }
+
+  /// The most verbose level written for `target`.
+  pub fn level(&self, target: &str) -> Level {
+    let matches = |name: &str| {
+      target
+        .strip_prefix(name)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+    };
+    self
+      .targets
+      .iter()
+      .filter(|(name, _)| matches(name))
+      .max_by_key(|(name, _)| name.len())
+      .map_or(self.default, |&(_, level)| level)
+  }
+
+  fn max_level(&self) -> Level {
+    let targets = self.targets.iter().map(|&(_, level)| level);
+    targets.fold(self.default, Level::max)
+  }
+}
+
+impl Default for Filter {
+  fn default() -> Self {
+    Self::all(Level::Warn)
+  }
+}
+
+impl FromStr for Filter {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut filter = Filter::default();
+    for entry in s.split(',').map(str::trim) {
+      match entry.split_once('=') {
+        Some((target, level)) if !target.is_empty() => {
+          filter.targets.retain(|(name, _)| name != target);
+          filter.targets.push((target.to_string(), level.parse()?));
+        }
+        Some(_) => return Err(format!("Log filter {:?} has no target", entry)),
+        None => filter.default = entry.parse()?,
+      }
+    }
+    Ok(filter)
+  }
+}
+
+impl fmt::Display for Filter {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.default.as_str())?;
+    for (target, level) in &self.targets {
+      write!(f, ",{}={}", target, level.as_str())?;
+    }
+    Ok(())
+  }
+}
+
+static FILTER: RwLock<Filter> = RwLock::new(Filter::all(Level::Warn));
+/// The most verbose level of `FILTER`, to skip most messages cheaply.
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+static SINK: Mutex<Option<Box<dyn Write + Send>>> = Mutex::new(None);
+
+pub fn set_filter(filter: Filter) {
+  MAX_LEVEL.store(filter.max_level() as u8, Ordering::Relaxed);
+  *FILTER.write().unwrap() = filter;
+}
+
+/// Writes messages to `sink` instead of stderr.
+pub fn set_sink(sink: Box<dyn Write + Send>) {
+  *SINK.lock().unwrap() = Some(sink);
+}
+
+/// The target for messages from `module_path`, e.g. `day11` for
+/// `aoc2019::day11`.
+pub fn target(module_path: &str) -> &str {
+  module_path
+    .split_once("::")
+    .map_or(module_path, |(_, rest)| rest)
+}
+
+/// Whether a message at `level` from `module_path` would be written. This
+/// is checked in hot loops like the Intcode VM's, so the usual case of a
+/// level that is filtered out everywhere stays cheap.
+#[inline]
+pub fn enabled(module_path: &str, level: Level) -> bool {
+  level as u8 <= MAX_LEVEL.load(Ordering::Relaxed) && target_enabled(module_path, level)
+}
+
+#[cold]
+fn target_enabled(module_path: &str, level: Level) -> bool {
+  level <= FILTER.read().unwrap().level(target(module_path))
+}
+
+#[doc(hidden)]
+#[cold]
+pub fn write(module_path: &str, level: Level, message: fmt::Arguments<'_>) {
+  let line = format!(
+    "[{:5} {}] {}\n",
+    level.as_str().to_uppercase(),
+    target(module_path),
+    message
+  );
+  let mut sink = SINK.lock().unwrap();
+  // There is nowhere left to report a failing sink
+  let _ = match sink.as_mut() {
+    Some(sink) => sink.write_all(line.as_bytes()),
+    None => io::stderr().write_all(line.as_bytes()),
+  };
+}
+
+/// Logs a message at a level, if the filter lets it through.
+#[macro_export]
+macro_rules! log {
+  ($level:expr, $($arg:tt)+) => {
+    if $crate::log::enabled(module_path!(), $level) {
+      $crate::log::write(module_path!(), $level, format_args!($($arg)+));
+    }
+  };
+}
+
+#[macro_export]
+macro_rules! error {
+  ($($arg:tt)+) => { $crate::log!($crate::log::Level::Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+  ($($arg:tt)+) => { $crate::log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+  ($($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+  ($($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+  ($($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::{self, Write};
+  use std::sync::{Arc, Mutex};
+
+  use super::{set_filter, set_sink, target, Filter, Level};
+
+  #[test]
+  fn filters() {
+    let filter: Filter = "intcode=trace, day11=debug".parse().unwrap();
+    assert_eq!(filter.level("intcode"), Level::Trace);
+    assert_eq!(filter.level("intcode::extension"), Level::Trace);
+    assert_eq!(filter.level("intcodes"), Level::Warn);
+    assert_eq!(filter.level("day11"), Level::Debug);
+    assert_eq!(filter.level("day07"), Level::Warn);
+    assert_eq!(filter.to_string(), "warn,intcode=trace,day11=debug");
+    assert_eq!(filter.to_string().parse(), Ok(filter));
+
+    let filter: Filter = "debug,intcode=off,intcode::extension=info".parse().unwrap();
+    assert_eq!(filter.level("day07"), Level::Debug);
+    assert_eq!(filter.level("intcode"), Level::Off);
+    assert_eq!(filter.level("intcode::extension"), Level::Info);
+    assert_eq!(filter.max_level(), Level::Debug);
+
+    assert!("intcode=loud".parse::<Filter>().is_err());
+    assert!("=debug".parse::<Filter>().is_err());
+    assert!("".parse::<Filter>().is_err());
+    assert_eq!(target("aoc2019::day11"), "day11");
+    assert_eq!(target("aoc2019::log::tests"), "log::tests");
+  }
+
+  #[derive(Clone)]
+  struct Capture(Arc<Mutex<Vec<u8>>>);
+
+  impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+      self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn writes_to_sink() {
+    let capture = Capture(Arc::new(Mutex::new(Vec::new())));
+    set_sink(Box::new(capture.clone()));
+    set_filter("log::tests=debug".parse().unwrap());
+    crate::debug!("{} panels", 3);
+    crate::trace!("not written");
+    crate::warn!("careful");
+    set_filter(Filter::default());
+    crate::debug!("not written either");
+
+    let written = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+    assert_eq!(
+      written,
+      "[DEBUG log::tests] 3 panels\n[WARN  log::tests] careful\n"
+    );
+  }
+}
@@ -1,34 +1,76 @@
 use std::env;
 use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use colored::*;
 
-use aoc2019::day01::DayOne;
-use aoc2019::day02::DayTwo;
-use aoc2019::day03::DayThree;
-use aoc2019::day04::DayFour;
-use aoc2019::day05::DayFive;
-use aoc2019::day06::DaySix;
-use aoc2019::day07::DaySeven;
-use aoc2019::day08::DayEight;
-use aoc2019::day09::DayNine;
-use aoc2019::day10::DayTen;
-use aoc2019::day11::DayEleven;
-use aoc2019::problem::Problem;
+use aoc2019::answers::{self, AnswerFile};
+use aoc2019::bench::{self, BenchResult, Stats};
+use aoc2019::cache::{self, Cache};
+use aoc2019::cli::{
+  self, BenchOptions, ColorChoice, Command, Format, GenerateOptions, Input, Parts, ReportOptions,
+  RunOptions, VerifyOptions, WatchOptions,
+};
+use aoc2019::input;
+use aoc2019::intcode::{self, binary};
+use aoc2019::log;
+use aoc2019::output::{self, Record, Status, Summary};
+use aoc2019::pool;
+use aoc2019::problem::{Answer, Problem, SolveError};
+use aoc2019::registry::{self, Day};
+use aoc2019::report;
+use aoc2019::rng::Rng;
+use aoc2019::scaffold;
+use aoc2019::timing::{self, Timings};
+use aoc2019::verify::{self, Outcome};
+use aoc2019::watch::{self, Snapshot};
 
 fn main() -> Result<(), String> {
-  let args: Vec<String> = env::args().collect();
+  let command = cli::parse(env::args().skip(1)).unwrap_or_else(|e| {
+    eprintln!("{}\n\n{}", e, cli::USAGE);
+    process::exit(2);
+  });
 
-  match args.get(1) {
-    Some(day) => {
-      print_problem(day.parse::<usize>().expect("Invalid day input!"), true)?;
+  if let Command::Run(options)
+  | Command::Bench(options, _)
+  | Command::Watch(options, _)
+  | Command::Report(options, _)
+  | Command::Verify(options, _) = &command
+  {
+    if let Some(filter) = &options.log {
+      log::set_filter(filter.clone());
     }
-    None => {
-      for day in 1..25 {
-        if print_problem(day, false).is_err() {
-          break;
-        }
-        println!();
+  }
+
+  match command {
+    Command::Help => println!("{}", cli::USAGE),
+    Command::Convert { from, to, optimize } => {
+      convert_program(&from, &to, optimize)
+        .map_err(|e| format!("Could not convert {}: {}", from, e))?;
+    }
+    Command::Generate(generate) => run_generate(&generate)?,
+    Command::NewDay { day, title } => {
+      let created = scaffold::create(Path::new(""), day, &title)
+        .map_err(|e| format!("Could not create day {:02}: {}", day, e))?;
+      for path in created {
+        println!("Created {}", path.display());
+      }
+    }
+    Command::Run(options) => {
+      if !run(&options)?.is_success() {
+        process::exit(1);
+      }
+    }
+    Command::Bench(options, bench) => run_bench(&options, &bench)?,
+    Command::Watch(options, watch) => run_watch(&options, &watch)?,
+    Command::Report(options, report) => run_report(&options, &report)?,
+    Command::Verify(options, verify) => {
+      if !run_verify(&options, &verify) {
+        process::exit(1);
       }
     }
   }
@@ -36,76 +78,852 @@ fn main() -> Result<(), String> {
   Ok(())
 }
 
-fn print_problem(day: usize, debug: bool) -> Result<(), String> {
-  let day_str = format!("{:02}", day);
+/// Runs the selected days, returning how many parts passed and failed.
+fn run(options: &RunOptions) -> Result<Summary, String> {
+  set_color(options.color);
+  if options.format == Format::Csv {
+    print!("{}", output::CSV_HEADER);
+  }
 
-  let problem =
-    get_problem(day, debug).ok_or_else(|| format!("Day {} not implemented!", day_str))?;
-  let input = fs::read_to_string(format!("inputs/day{}.txt", day_str)).unwrap();
+  let days = selected_days(options);
+  let (records, wall) = solve_all(&days, options, |jobs, records, first| {
+    print_day(jobs, records, options.format, first)
+  })?;
 
-  println!("{}", format!("DAY {}", day_str).blue().bold());
-  print_part(1, &problem.soln_one(), &problem.part_one(&input));
-  print_part(2, &problem.soln_two(), &problem.part_two(&input));
+  match options.format {
+    Format::Text if days.len() > 1 => {
+      print_summary(&records);
+      print_times(
+        &records,
+        wall,
+        pool::threads(options.jobs).min(records.len()),
+      );
+    }
+    Format::Json => println!("{}", output::to_json(&records)),
+    _ => (),
+  }
+  Ok(Summary::of(&records))
+}
 
-  Ok(())
+/// Solves `days`, calling `on_day` with each day's jobs and records as soon
+/// as they are done (and whether it is the first day). Returns every record
+/// and how long solving took.
+fn solve_all<F>(
+  days: &[&'static Day],
+  options: &RunOptions,
+  mut on_day: F,
+) -> Result<(Vec<Record>, Duration), String>
+where
+  F: FnMut(&[Job], &[Record], bool),
+{
+  let answers_path = options
+    .answers
+    .clone()
+    .unwrap_or_else(|| PathBuf::from(answers::DEFAULT_PATH));
+  let mut answers = AnswerFile::load(&answers_path)
+    .map_err(|e| format!("Could not load {:?}: {}", answers_path, e))?;
+
+  let cache_path = Path::new(cache::DEFAULT_PATH);
+  if options.clear_cache {
+    Cache::clear(cache_path).map_err(|e| format!("Could not clear {:?}: {}", cache_path, e))?;
+  }
+  // Logging is for seeing the solvers run
+  let mut cache =
+    Some(Cache::load(cache_path)).filter(|_| !options.no_cache && options.log.is_none());
+
+  let jobs = plan_jobs(days, options);
+
+  // Answers are recorded and cached once every part is solved, since the
+  // workers share those files.
+  let start = Instant::now();
+  let mut records = Vec::new();
+  let mut solved = Vec::new();
+  let mut day_start = 0;
+  pool::run_ordered(
+    options.jobs,
+    &jobs,
+    |job| solve_job(job, &answers, cache.as_ref()),
+    |i, (record, solution)| {
+      records.push(record);
+      if let Some(solution) = solution {
+        solved.push((i, solution));
+      }
+      if jobs
+        .get(i + 1)
+        .is_none_or(|next| next.day.number != jobs[i].day.number)
+      {
+        on_day(&jobs[day_start..=i], &records[day_start..], day_start == 0);
+        day_start = i + 1;
+      }
+    },
+  );
+  let wall = start.elapsed();
+
+  if let Some(cache) = &mut cache {
+    let mut changed = false;
+    for (i, (answer, timings)) in &solved {
+      let job = &jobs[*i];
+      if let (Ok(input), false) = (&job.input, records[*i].cached) {
+        let day = job.day;
+        cache.insert(day.number, day.version, job.part, input, answer, timings);
+        changed = true;
+      }
+    }
+    if let (true, Err(e)) = (changed, cache.save()) {
+      eprintln!("Could not save the cache to {:?}: {}", cache_path, e);
+    }
+  }
+
+  if options.record {
+    for (i, (answer, _)) in &solved {
+      let job = &jobs[*i];
+      if let Ok(input) = &job.input {
+        answers.record(job.day.number, job.part, input, answer);
+      }
+    }
+    answers
+      .save()
+      .map_err(|e| format!("Could not save {:?}: {}", answers_path, e))?;
+    eprintln!("Recorded the answers to {}", answers_path.display());
+  }
+  Ok((records, wall))
+}
+
+/// Solves the selected days and writes the Markdown and HTML reports.
+fn run_report(options: &RunOptions, report: &ReportOptions) -> Result<(), String> {
+  set_color(options.color);
+  let (records, _) = solve_all(&selected_days(options), options, |jobs, _, _| {
+    eprintln!("Solved day {:02}", jobs[0].day.number)
+  })?;
+  print_summary(&records);
+
+  let write = |name: &str, contents: String| {
+    let path = report.out.join(name);
+    fs::create_dir_all(&report.out)
+      .and_then(|_| fs::write(&path, contents))
+      .map_err(|e| format!("Could not write {:?}: {}", path, e))?;
+    println!("Wrote {}", path.display());
+    Ok::<_, String>(())
+  };
+  write("report.md", report::markdown(&records))?;
+  write("report.html", report::html(&records))
 }
 
-fn print_part(num: usize, expected: &Option<String>, actual: &Option<String>) {
+/// An input to verify against, where it came from, and which generated
+/// case it is, if any.
+type VerifyInput = (String, String, Option<usize>);
+
+/// Checks the selected days against their reference solvers, on their inputs
+/// and on generated ones. Returns whether every part agreed.
+fn run_verify(options: &RunOptions, verify: &VerifyOptions) -> bool {
+  set_color(options.color);
+  let seed = verify.seed.unwrap_or_else(random_seed);
+  println!("{}", format!("Seed {}", seed).dimmed());
+
+  let mut all_agree = true;
+  for day in selected_days(options) {
+    println!();
+    print_header(day);
+    let problem = day.create();
+    let mut inputs: Vec<VerifyInput> = match read_day_inputs(day, options) {
+      Ok(inputs) => inputs
+        .into_iter()
+        .map(|(source, input)| (source, input, None))
+        .collect(),
+      Err(error) => {
+        println!("{}", error.red());
+        Vec::new()
+      }
+    };
+    for case in 0..verify.cases {
+      let mut rng = Rng::for_input(seed, day.number, case);
+      match problem.generate_input(&mut rng, verify.size) {
+        Some(input) => inputs.push((format!("generated input {}", case + 1), input, Some(case))),
+        None => break,
+      }
+    }
+    for part in selected_parts(options) {
+      all_agree &= verify_part(day, problem.as_ref(), part, &inputs);
+    }
+  }
+  all_agree
+}
+
+/// Prints whether `part` agrees with its reference on each input, saving
+/// generated inputs it does not agree on. Inputs both solvers reject the
+/// same way are counted separately. Returns whether it never disagreed.
+fn verify_part(day: &Day, problem: &dyn Problem, part: usize, inputs: &[VerifyInput]) -> bool {
+  let show = |result: &Result<Answer, SolveError>| match result {
+    Ok(answer) => answer.to_string(),
+    Err(error) => error.to_string(),
+  };
+
+  let mut agreed = 0;
+  let mut skipped = 0;
+  for (source, input, case) in inputs {
+    match verify::compare(problem, part, input) {
+      Outcome::NoReference => {
+        println!("Part {}: {}", part, "no reference solver".yellow());
+        return true;
+      }
+      Outcome::Agree => agreed += 1,
+      Outcome::Skipped(_) => skipped += 1,
+      Outcome::Disagree { actual, reference } => {
+        let source = match case {
+          Some(case) => save_failed(day, *case, input).map_or_else(
+            |e| format!("{} (could not save it: {})", source, e),
+            |path| format!("{} (saved to {})", source, path.display()),
+          ),
+          None => source.clone(),
+        };
+        let message = format!(
+          "Part {}: {} gives {}, but the reference gives {}",
+          part,
+          source,
+          show(&actual),
+          show(&reference)
+        );
+        println!("{}", message.red());
+      }
+    }
+  }
+
+  let status = if agreed + skipped < inputs.len() {
+    Status::Fail
+  } else if agreed == 0 {
+    Status::Unknown
+  } else {
+    Status::Pass
+  };
+  let skipped = match skipped {
+    0 => String::new(),
+    n => format!(", {} skipped as both solvers reject them", n),
+  };
   println!(
-    "Part {}: (expected answer: {})",
-    num,
-    expected.as_ref().unwrap_or(&"unknown".to_string()).bold()
+    "Part {}: {} of {} inputs agree{} {}",
+    part,
+    agreed,
+    inputs.len(),
+    skipped,
+    mark(status, 0)
   );
-  println!(
-    "Actual: {} {}",
-    actual
-      .as_ref()
-      .unwrap_or(&"unimplemented".to_string())
-      .bold(),
-    result(&expected, &actual)
+  status != Status::Fail
+}
+
+/// Saves a generated input that the solvers disagree on, to look at later.
+fn save_failed(day: &Day, case: usize, input: &str) -> io::Result<PathBuf> {
+  let dir = Path::new(verify::FAILED_DIR);
+  let path = dir.join(format!("day{:02}-{}.txt", day.number, case + 1));
+  fs::create_dir_all(dir)?;
+  fs::write(&path, input)?;
+  Ok(path)
+}
+
+/// One part of a day to solve, for one of its inputs.
+struct Job {
+  day: &'static Day,
+  /// Where the input came from.
+  source: String,
+  /// The input, or why it could not be read.
+  input: Result<String, String>,
+  part: usize,
+}
+
+/// A job for each selected part of each input of each day, in day order.
+/// Days whose inputs cannot be read still get a job per part, which fails.
+fn plan_jobs(days: &[&'static Day], options: &RunOptions) -> Vec<Job> {
+  let mut jobs = Vec::new();
+  for &day in days {
+    let inputs = match read_day_inputs(day, options) {
+      Ok(inputs) => inputs
+        .into_iter()
+        .map(|(source, input)| (source, Ok(input)))
+        .collect(),
+      Err(error) => vec![(String::new(), Err(error))],
+    };
+    for (source, input) in inputs {
+      for part in selected_parts(options) {
+        jobs.push(Job {
+          day,
+          source: source.clone(),
+          input: input.clone(),
+          part,
+        });
+      }
+    }
+  }
+  jobs
+}
+
+/// Solves a job, or looks its answer up in `cache`. Returns its record and
+/// the answer with its timings, if there is one.
+fn solve_job(
+  job: &Job,
+  answers: &AnswerFile,
+  cache: Option<&Cache>,
+) -> (Record, Option<(Answer, Timings)>) {
+  let day = job.day;
+  let input = match &job.input {
+    Ok(input) => input,
+    Err(error) => {
+      let record = Record::failed(day.number, day.title, "", job.part, error);
+      return (record, None);
+    }
+  };
+
+  let expected = answers.expected(day.number, job.part, input);
+  let cached = cache.and_then(|cache| cache.get(day.number, day.version, job.part, input));
+  let is_cached = cached.is_some();
+  let (actual, timings) = match cached {
+    Some((answer, timings)) => (Ok(answer), timings),
+    None => solve(day.create().as_ref(), job.part, input),
+  };
+  let mut record = Record::new(
+    day.number,
+    day.title,
+    &job.source,
+    job.part,
+    &expected,
+    &actual,
+    &timings,
   );
+  record.cached = is_cached;
+  (record, actual.ok().map(|answer| (answer, timings)))
+}
+
+fn run_bench(options: &RunOptions, bench: &BenchOptions) -> Result<(), String> {
+  set_color(options.color);
+  let baseline = match &bench.compare {
+    Some(path) => bench::load(path).map_err(|e| format!("Could not load {:?}: {}", path, e))?,
+    None => Vec::new(),
+  };
+
+  if !bench.sizes.is_empty() {
+    run_scaling(options, bench);
+    return Ok(());
+  }
+
+  let mut results = Vec::new();
+  let mut regressions = 0;
+  for day in selected_days(options) {
+    print_header(day);
+    // Only the first input is benchmarked, so results stay comparable
+    let input = match read_day_inputs(day, options) {
+      Ok(mut inputs) => inputs.remove(0).1,
+      Err(error) => {
+        println!("{}\n", error.red());
+        continue;
+      }
+    };
+    let problem = day.create();
+
+    for part in selected_parts(options) {
+      let stats = match bench_part(problem.as_ref(), part, &input, bench) {
+        Ok(stats) => stats,
+        Err(e) => {
+          println!("Part {}: {}", part, e.to_string().red());
+          continue;
+        }
+      };
+      let result = BenchResult {
+        day: day.number,
+        part,
+        stats,
+      };
+
+      print!(
+        "Part {}: min {} median {} stddev {}",
+        part,
+        timing::format(stats.min).bold(),
+        timing::format(stats.median).bold(),
+        timing::format(stats.stddev)
+      );
+      match bench::compare(&baseline, &result) {
+        Some(comparison) if comparison.is_regression(bench.threshold) => {
+          regressions += 1;
+          let change = format!("{:+.1}% regression", comparison.change() * 100.);
+          println!(" {}", change.red().bold());
+        }
+        Some(comparison) => println!(" {:+.1}%", comparison.change() * 100.),
+        None => println!(),
+      }
+      results.push(result);
+    }
+    println!();
+  }
+
+  if let Some(path) = &bench.save {
+    bench::save(path, &results).map_err(|e| format!("Could not save {:?}: {}", path, e))?;
+  }
+  if regressions > 0 {
+    return Err(format!("{} part(s) regressed", regressions));
+  }
+  Ok(())
+}
+
+/// Solves `part` over and over, returning the statistics of the measured
+/// runs, or the first error.
+fn bench_part(
+  problem: &dyn Problem,
+  part: usize,
+  input: &str,
+  bench: &BenchOptions,
+) -> Result<Stats, SolveError> {
+  let mut samples = Vec::with_capacity(bench.runs);
+  for run in 0..bench.warmup + bench.runs {
+    let (answer, timings) = solve(problem, part, input);
+    answer?;
+    if run >= bench.warmup {
+      samples.push(timings.total());
+    }
+  }
+  // There is at least one run
+  Ok(Stats::from_samples(&samples).unwrap())
 }
 
-fn result(expected: &Option<String>, actual: &Option<String>) -> String {
-  if expected.is_none() {
-    "??".yellow().to_string()
-  } else if expected == actual {
-    "✓".green().to_string()
+/// Benchmarks each part on generated inputs of every size in `--sizes`,
+/// showing how the median time grows from one size to the next.
+fn run_scaling(options: &RunOptions, bench: &BenchOptions) {
+  for day in selected_days(options) {
+    print_header(day);
+    let problem = day.create();
+    let inputs: Option<Vec<(usize, String)>> = bench
+      .sizes
+      .iter()
+      .map(|&size| {
+        let mut rng = Rng::for_input(bench.seed, day.number, size);
+        problem
+          .generate_input(&mut rng, size)
+          .map(|input| (size, input))
+      })
+      .collect();
+    let inputs = match inputs {
+      Some(inputs) => inputs,
+      None => {
+        println!("{}\n", "No input generator".dimmed());
+        continue;
+      }
+    };
+
+    for part in selected_parts(options) {
+      println!("Part {}:", part);
+      let mut previous: Option<(usize, Duration)> = None;
+      for (size, input) in &inputs {
+        let stats = match bench_part(problem.as_ref(), part, input, bench) {
+          Ok(stats) => stats,
+          Err(e) => {
+            println!("  size {:<8} {}", size, e.to_string().red());
+            previous = None;
+            continue;
+          }
+        };
+        print!(
+          "  size {:<8} median {} stddev {}",
+          size,
+          timing::format(stats.median).bold(),
+          timing::format(stats.stddev)
+        );
+        match previous {
+          Some(previous) if previous.0 != *size => println!(
+            " (~n^{:.1})",
+            bench::growth(previous, (*size, stats.median))
+          ),
+          _ => println!(),
+        }
+        previous = Some((*size, stats.median));
+      }
+    }
+    println!();
+  }
+}
+
+/// Writes a generated input for a day to stdout, or to `--out`.
+fn run_generate(generate: &GenerateOptions) -> Result<(), String> {
+  let day = registry::get(generate.day)
+    .ok_or_else(|| format!("Day {:02} is not implemented", generate.day))?;
+  let seed = generate.seed.unwrap_or_else(|| {
+    let seed = random_seed();
+    eprintln!("Seed {}", seed);
+    seed
+  });
+  let mut rng = Rng::for_input(seed, day.number, 0);
+  let input = day
+    .create()
+    .generate_input(&mut rng, generate.size)
+    .ok_or_else(|| format!("Day {:02} can not generate inputs", day.number))?;
+  match &generate.out {
+    Some(path) => fs::write(path, input).map_err(|e| format!("Could not write {:?}: {}", path, e)),
+    None => {
+      print!("{}", input);
+      Ok(())
+    }
+  }
+}
+
+/// A seed from the current time, for when none is given.
+fn random_seed() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |time| time.as_nanos() as u64)
+}
+
+/// Re-runs each selected day whenever its source or inputs change. Shared
+/// sources re-run every day. The days run in a fresh `cargo run`, so source
+/// changes are rebuilt.
+fn run_watch(options: &RunOptions, watch: &WatchOptions) -> Result<(), String> {
+  set_color(options.color);
+  let days: Vec<&Day> = selected_days(options);
+  let input = match &options.input {
+    Some(Input::Path(path)) => Some(path.as_path()),
+    _ => None,
+  };
+
+  let mut shared = Snapshot::new();
+  let mut snapshots: Vec<Snapshot> = vec![Snapshot::new(); days.len()];
+  let mut previous: Vec<Record> = Vec::new();
+  loop {
+    let files = watch::shared_files().map_err(|e| format!("Could not list sources: {}", e))?;
+    let shared_now = watch::snapshot(&files);
+    let shared_changed = shared_now != shared;
+    shared = shared_now;
+
+    let mut changed = Vec::new();
+    for (day, snapshot) in days.iter().zip(&mut snapshots) {
+      let now = watch::snapshot(&watch::day_files(day.number, input));
+      if shared_changed || now != *snapshot {
+        changed.push(*day);
+      }
+      *snapshot = now;
+    }
+
+    if !changed.is_empty() {
+      match rerun(&changed, options) {
+        Ok(records) => {
+          print_changes(&changed, &previous, &records);
+          previous.retain(|old| !records.iter().any(|new| new.day == old.day));
+          previous.extend(records);
+        }
+        Err(error) => println!("{}", error.red()),
+      }
+      println!("{}", "Waiting for changes...".dimmed());
+    }
+    thread::sleep(watch.interval);
+  }
+}
+
+/// Runs `days` in a fresh `cargo run` of the same profile, returning the
+/// records it printed.
+fn rerun(days: &[&Day], options: &RunOptions) -> Result<Vec<Record>, String> {
+  let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+  let mut command = process::Command::new(cargo);
+  command.args(["run", "--quiet"]);
+  if !cfg!(debug_assertions) {
+    command.arg("--release");
+  }
+  let day_list: Vec<String> = days.iter().map(|day| day.number.to_string()).collect();
+  command.args(["--", &day_list.join(","), "--format", "json", "--no-cache"]);
+  command.args(["--jobs", &options.jobs.to_string()]);
+  match options.parts {
+    Parts::One => command.args(["--part", "1"]),
+    Parts::Two => command.args(["--part", "2"]),
+    Parts::Both => &mut command,
+  };
+  if let Some(Input::Path(path)) = &options.input {
+    command.arg("--input").arg(path);
+  }
+  if let Some(path) = &options.answers {
+    command.arg("--answers").arg(path);
+  }
+  if let Some(filter) = &options.log {
+    command.arg("--log").arg(filter.to_string());
+  }
+
+  // Build errors go straight to stderr
+  let output = command
+    .stderr(process::Stdio::inherit())
+    .output()
+    .map_err(|e| format!("Could not run cargo: {}", e))?;
+  serde_json::from_slice(&output.stdout).map_err(|_| "The run failed".to_string())
+}
+
+/// Prints each day's new results next to the previous ones.
+fn print_changes(days: &[&Day], previous: &[Record], records: &[Record]) {
+  for day in days {
+    println!();
+    print_header(day);
+    let day_records: Vec<&Record> = records.iter().filter(|r| r.day == day.number).collect();
+    let several = day_records.iter().any(|r| r.input != day_records[0].input);
+    for (i, record) in day_records.iter().enumerate() {
+      if several && (i == 0 || day_records[i - 1].input != record.input) {
+        println!("{}", format!("Input {}", record.input).bold());
+      }
+      let before = previous
+        .iter()
+        .find(|old| old.day == record.day && old.input == record.input && old.part == record.part);
+      let line = watch::describe(before, record);
+      println!("{} {}", mark(record.status, 0), line);
+    }
+  }
+}
+
+fn set_color(choice: ColorChoice) {
+  match choice {
+    ColorChoice::Auto => control::set_override(io::stdout().is_terminal()),
+    ColorChoice::Always => control::set_override(true),
+    ColorChoice::Never => control::set_override(false),
+  }
+}
+
+/// The selected days that are registered. Days can be added out of order,
+/// so gaps in a range are skipped with a note.
+fn selected_days(options: &RunOptions) -> Vec<&'static Day> {
+  if options.days.is_empty() {
+    return registry::all().iter().collect();
+  }
+  options
+    .days
+    .iter()
+    .filter_map(|&day| {
+      let registered = registry::get(day);
+      if registered.is_none() {
+        eprintln!(
+          "{}",
+          format!("Skipping day {:02}: not implemented", day).dimmed()
+        );
+      }
+      registered
+    })
+    .collect()
+}
+
+fn selected_parts(options: &RunOptions) -> impl Iterator<Item = usize> + '_ {
+  (1..=2).filter(move |&part| options.parts.includes(part))
+}
+
+/// Reads every input for `day`, along with where each came from, with
+/// normalized line endings. Returns an error unless there is at least one.
+fn read_day_inputs(day: &Day, options: &RunOptions) -> Result<Vec<(String, String)>, String> {
+  let read_error = |e| format!("Could not read input: {}", e);
+  match &options.input {
+    Some(Input::Stdin) => {
+      let mut input = String::new();
+      io::stdin().read_to_string(&mut input).map_err(read_error)?;
+      Ok(vec![("stdin".to_string(), input::normalize(&input))])
+    }
+    Some(Input::Path(path)) => {
+      let input = fs::read_to_string(path).map_err(read_error)?;
+      Ok(vec![(path.display().to_string(), input::normalize(&input))])
+    }
+    None => {
+      let paths = input::find_inputs(input::DEFAULT_DIR, day.number).map_err(read_error)?;
+      if paths.is_empty() {
+        return Err(format!(
+          "Could not find inputs/day{0:02}.txt or inputs/day{0:02}/*.txt",
+          day.number
+        ));
+      }
+      paths
+        .iter()
+        .map(|path| {
+          let input = fs::read_to_string(path).map_err(read_error)?;
+          Ok((path.display().to_string(), input::normalize(&input)))
+        })
+        .collect()
+    }
+  }
+}
+
+fn solve(problem: &dyn Problem, part: usize, input: &str) -> (Result<Answer, SolveError>, Timings) {
+  timing::measure(|| match part {
+    1 => problem.part_one(input),
+    _ => problem.part_two(input),
+  })
+}
+
+/// Converts an Intcode program between the text and binary formats.
+/// The output is written in whichever format the input is not, after
+/// rewriting the program with `intcode::optimize` if `optimize` is set.
+fn convert_program(from: &str, to: &str, optimize: bool) -> std::io::Result<()> {
+  let data = fs::read(from)?;
+  let to_text = binary::is_binary(&data);
+  let mut program = binary::from_bytes(data)?;
+  if optimize {
+    let (optimized, stats) = intcode::optimize::optimize(&program);
+    eprintln!(
+      "Optimized {}: {} folded, {} moves, {} jumps",
+      from, stats.folded, stats.moves, stats.jumps
+    );
+    program = optimized;
+  }
+  if to_text {
+    let text: Vec<String> = program.iter().map(i64::to_string).collect();
+    fs::write(to, text.join(","))
   } else {
-    "✗".red().to_string()
-  }
-}
-
-fn get_problem(day: usize, debug: bool) -> Option<Box<dyn Problem>> {
-  if debug {
-    match day {
-      1 => Some(Box::new(DayOne::debug())),
-      2 => Some(Box::new(DayTwo::debug())),
-      3 => Some(Box::new(DayThree::debug())),
-      4 => Some(Box::new(DayFour::debug())),
-      5 => Some(Box::new(DayFive::debug())),
-      6 => Some(Box::new(DaySix::debug())),
-      7 => Some(Box::new(DaySeven::debug())),
-      8 => Some(Box::new(DayEight::debug())),
-      9 => Some(Box::new(DayNine::debug())),
-      10 => Some(Box::new(DayTen::debug())),
-      11 => Some(Box::new(DayEleven::debug())),
-      _ => None,
+    intcode::save_program(to, &program)
+  }
+}
+
+/// Prints the records of one day's jobs as text or CSV. JSON is printed
+/// once every day is done.
+fn print_day(jobs: &[Job], records: &[Record], format: Format, first: bool) {
+  match format {
+    Format::Text => {
+      if !first {
+        println!();
+      }
+      print_header(jobs[0].day);
+      match &jobs[0].input {
+        Ok(_) => print_records(records),
+        Err(error) => println!("{}", error.red()),
+      }
     }
+    Format::Csv => print!("{}", output::to_csv(records)),
+    Format::Json => (),
+  }
+}
+
+fn print_header(day: &Day) {
+  let header = format!("DAY {:02}: {}", day.number, day.title);
+  println!("{}", header.blue().bold());
+}
+
+/// Prints one row per day and input with the status of each part.
+fn print_summary(records: &[Record]) {
+  const PART_WIDTH: usize = 8;
+  let title_width = records.iter().map(|r| r.title.len()).max().unwrap_or(0);
+  let input_width = records.iter().map(|r| r.input.len()).max().unwrap_or(0);
+
+  println!();
+  println!("{}", "SUMMARY".blue().bold());
+  println!(
+    "{}",
+    format!(
+      "Day  {:title_width$}  {:input_width$}  {:PART_WIDTH$}{:PART_WIDTH$}Time",
+      "Title", "Input", "Part 1", "Part 2"
+    )
+    .bold()
+  );
+  for day_records in records.chunk_by(|a, b| a.day == b.day && a.input == b.input) {
+    let first = &day_records[0];
+    let part = |num| match day_records.iter().find(|r| r.part == num) {
+      Some(record) => mark(record.status, PART_WIDTH),
+      None => format!("{:PART_WIDTH$}", "-").normal(),
+    };
+    let total: u64 = day_records.iter().map(|r| r.parse_ns + r.solve_ns).sum();
+    let cached = if day_records.iter().any(|r| r.cached) {
+      " (cached)"
+    } else {
+      ""
+    };
+    println!(
+      "{:02}   {:title_width$}  {:input_width$}  {}{}{}{}",
+      first.day,
+      first.title,
+      first.input,
+      part(1),
+      part(2),
+      timing::format(Duration::from_nanos(total)),
+      cached.dimmed()
+    );
+  }
+
+  let summary = Summary::of(records);
+  let counts = format!(
+    "{} passed, {} failed, {} errors, {} unknown, {} unimplemented",
+    summary.pass, summary.fail, summary.error, summary.unknown, summary.unimplemented
+  );
+  if summary.is_success() {
+    println!("{}", counts.green());
   } else {
-    match day {
-      1 => Some(Box::new(DayOne::new())),
-      2 => Some(Box::new(DayTwo::new())),
-      3 => Some(Box::new(DayThree::new())),
-      4 => Some(Box::new(DayFour::new())),
-      5 => Some(Box::new(DayFive::new())),
-      6 => Some(Box::new(DaySix::new())),
-      7 => Some(Box::new(DaySeven::new())),
-      8 => Some(Box::new(DayEight::new())),
-      9 => Some(Box::new(DayNine::new())),
-      10 => Some(Box::new(DayTen::new())),
-      11 => Some(Box::new(DayEleven::new())),
-      _ => None,
+    println!("{}", counts.red());
+  }
+}
+
+/// Prints how long solving took, and how much of that was spent in the
+/// solvers summed over all threads. Cached parts did not run, so they are
+/// left out.
+fn print_times(records: &[Record], wall: Duration, threads: usize) {
+  let solved = records.iter().filter(|r| !r.cached);
+  let cpu: u64 = solved.map(|r| r.parse_ns + r.solve_ns).sum();
+  let threads = match threads {
+    1 => "1 thread".to_string(),
+    n => format!("{} threads", n),
+  };
+  let mut times = format!(
+    "Wall time {}, CPU time {} on {}",
+    timing::format(wall),
+    timing::format(Duration::from_nanos(cpu)),
+    threads
+  );
+  match records.iter().filter(|r| r.cached).count() {
+    0 => (),
+    1 => times.push_str(", 1 part cached"),
+    n => times.push_str(&format!(", {} parts cached", n)),
+  }
+  println!("{}", times.dimmed());
+}
+
+/// Prints a day's records. If it has several inputs, each gets a header.
+fn print_records(records: &[Record]) {
+  let several = records.iter().any(|r| r.input != records[0].input);
+  for (i, record) in records.iter().enumerate() {
+    if several && (i == 0 || records[i - 1].input != record.input) {
+      println!("{}", format!("Input {}", record.input).bold());
+    }
+    print_part(record);
+  }
+}
+
+fn print_part(record: &Record) {
+  let expected = match &record.expected {
+    // Grids do not fit on one line
+    Some(answer) if answer.contains('\n') => format!("{} rows", answer.lines().count()),
+    Some(answer) => answer.clone(),
+    None => "unknown".to_string(),
+  };
+  println!(
+    "Part {}: (expected answer: {})",
+    record.part,
+    expected.bold()
+  );
+
+  let mark = format!("{} {}", mark(record.status, 0), format_timings(record));
+  match (&record.actual, &record.error) {
+    (Some(answer), _) if answer.contains('\n') => {
+      println!("Actual: {}", mark);
+      for row in answer.lines() {
+        println!("  {}", row.bold());
+      }
+    }
+    (Some(answer), _) => println!("Actual: {} {}", answer.bold(), mark),
+    (None, _) if record.status == Status::Unimplemented => {
+      println!("Actual: {} {}", "unimplemented".bold(), mark)
     }
+    (None, error) => println!(
+      "Actual: {} {}",
+      error.as_deref().unwrap_or_default().red().bold(),
+      mark
+    ),
+  }
+}
+
+fn format_timings(record: &Record) -> ColoredString {
+  let cached = if record.cached { "cached, " } else { "" };
+  format!(
+    "({}parse {}, solve {})",
+    cached,
+    timing::format(Duration::from_nanos(record.parse_ns)),
+    timing::format(Duration::from_nanos(record.solve_ns))
+  )
+  .dimmed()
+}
+
+/// The status marker, padded to `width` before coloring.
+fn mark(status: Status, width: usize) -> ColoredString {
+  let padded = |symbol| format!("{:width$}", symbol);
+  match status {
+    Status::Pass => padded("✓").green(),
+    Status::Fail | Status::Error => padded("✗").red(),
+    Status::Unknown | Status::Unimplemented => padded("??").yellow(),
   }
 }
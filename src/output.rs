@@ -0,0 +1,281 @@
+//! Machine-readable results: one record per day and part, written as JSON
+//! or CSV.
+
+use serde::{Deserialize, Serialize};
+
+use crate::problem::{Answer, SolveError};
+use crate::timing::Timings;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+  Pass,
+  Fail,
+  /// The part was solved, but there is no known answer to check against.
+  Unknown,
+  Unimplemented,
+  Error,
+}
+
+impl Status {
+  pub fn as_str(self) -> &'static str {
+    match self {
+      Status::Pass => "pass",
+      Status::Fail => "fail",
+      Status::Unknown => "unknown",
+      Status::Unimplemented => "unimplemented",
+      Status::Error => "error",
+    }
+  }
+
+  pub fn of(expected: &Answer, actual: &Result<Answer, SolveError>) -> Self {
+    match actual {
+      Err(SolveError::Unimplemented) => Status::Unimplemented,
+      Err(_) => Status::Error,
+      Ok(_) if !expected.is_known() => Status::Unknown,
+      Ok(actual) if actual == expected => Status::Pass,
+      Ok(_) => Status::Fail,
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+  pub day: usize,
+  pub title: String,
+  /// Where the input came from, e.g. `inputs/day07/alice.txt`.
+  pub input: String,
+  pub part: usize,
+  pub status: Status,
+  pub expected: Option<String>,
+  pub actual: Option<String>,
+  pub error: Option<String>,
+  pub parse_ns: u64,
+  pub solve_ns: u64,
+  /// Whether the answer and timings came from the cache rather than a run.
+  #[serde(default)]
+  pub cached: bool,
+}
+
+impl Record {
+  pub fn new(
+    day: usize,
+    title: &str,
+    input: &str,
+    part: usize,
+    expected: &Answer,
+    actual: &Result<Answer, SolveError>,
+    timings: &Timings,
+  ) -> Self {
+    Self {
+      day,
+      title: title.to_string(),
+      input: input.to_string(),
+      part,
+      status: Status::of(expected, actual),
+      expected: Some(expected)
+        .filter(|e| e.is_known())
+        .map(Answer::to_string),
+      actual: actual.as_ref().ok().map(Answer::to_string),
+      error: actual.as_ref().err().map(SolveError::to_string),
+      parse_ns: timings.parse.as_nanos() as u64,
+      solve_ns: timings.solve.as_nanos() as u64,
+      cached: false,
+    }
+  }
+
+  /// A part that could not be run at all, e.g. because its input is missing.
+  pub fn failed(day: usize, title: &str, input: &str, part: usize, error: &str) -> Self {
+    Self {
+      day,
+      title: title.to_string(),
+      input: input.to_string(),
+      part,
+      status: Status::Error,
+      expected: None,
+      actual: None,
+      error: Some(error.to_string()),
+      parse_ns: 0,
+      solve_ns: 0,
+      cached: false,
+    }
+  }
+}
+
+/// How many parts ended up in each status.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Summary {
+  pub pass: usize,
+  pub fail: usize,
+  pub unknown: usize,
+  pub unimplemented: usize,
+  pub error: usize,
+}
+
+impl Summary {
+  pub fn of(records: &[Record]) -> Self {
+    let mut summary = Self::default();
+    for record in records {
+      match record.status {
+        Status::Pass => summary.pass += 1,
+        Status::Fail => summary.fail += 1,
+        Status::Unknown => summary.unknown += 1,
+        Status::Unimplemented => summary.unimplemented += 1,
+        Status::Error => summary.error += 1,
+      }
+    }
+    summary
+  }
+
+  /// Whether no part gave a wrong answer or failed to run.
+  pub fn is_success(&self) -> bool {
+    self.fail == 0 && self.error == 0
+  }
+}
+
+pub fn to_json(records: &[Record]) -> String {
+  serde_json::to_string_pretty(records).expect("Records are always serializable")
+}
+
+pub const CSV_HEADER: &str =
+  "day,title,input,part,status,expected,actual,error,parse_ns,solve_ns,cached\n";
+
+/// Formats the records as CSV rows, without the header.
+pub fn to_csv(records: &[Record]) -> String {
+  let mut out = String::new();
+  for record in records {
+    let fields = [
+      record.day.to_string(),
+      csv_field(&record.title),
+      csv_field(&record.input),
+      record.part.to_string(),
+      record.status.as_str().to_string(),
+      csv_field(record.expected.as_deref().unwrap_or_default()),
+      csv_field(record.actual.as_deref().unwrap_or_default()),
+      csv_field(record.error.as_deref().unwrap_or_default()),
+      record.parse_ns.to_string(),
+      record.solve_ns.to_string(),
+      record.cached.to_string(),
+    ];
+    out.push_str(&fields.join(","));
+    out.push('\n');
+  }
+  out
+}
+
+/// Quotes a field if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+  if field.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::{to_csv, to_json, Record, Status, Summary};
+  use crate::problem::{Answer, SolveError};
+  use crate::timing::Timings;
+
+  fn timings() -> Timings {
+    Timings {
+      parse: Duration::from_nanos(1500),
+      solve: Duration::from_micros(20),
+    }
+  }
+
+  #[test]
+  fn status() {
+    let ok = Ok(Answer::Integer(7));
+    assert_eq!(Status::of(&Answer::Integer(7), &ok), Status::Pass);
+    assert_eq!(Status::of(&Answer::Integer(8), &ok), Status::Fail);
+    assert_eq!(Status::of(&Answer::Unknown, &ok), Status::Unknown);
+    let err = Err(SolveError::invalid("no"));
+    assert_eq!(Status::of(&Answer::Integer(7), &err), Status::Error);
+    let unimplemented = Err(SolveError::Unimplemented);
+    assert_eq!(
+      Status::of(&Answer::Unknown, &unimplemented),
+      Status::Unimplemented
+    );
+  }
+
+  #[test]
+  fn json() {
+    let record = Record::new(
+      1,
+      "Title",
+      "inputs/day01.txt",
+      2,
+      &Answer::Integer(7),
+      &Ok(Answer::Integer(7)),
+      &timings(),
+    );
+    let json: serde_json::Value =
+      serde_json::from_str(&to_json(std::slice::from_ref(&record))).unwrap();
+    assert_eq!(
+      json,
+      serde_json::json!([{
+        "day": 1,
+        "title": "Title",
+        "input": "inputs/day01.txt",
+        "part": 2,
+        "status": "pass",
+        "expected": "7",
+        "actual": "7",
+        "error": null,
+        "parse_ns": 1500,
+        "solve_ns": 20000,
+        "cached": false,
+      }])
+    );
+    let parsed: Vec<Record> = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, vec![record]);
+  }
+
+  #[test]
+  fn csv() {
+    let mut grid = Record::new(
+      8,
+      "Space, Images",
+      "inputs/day08.txt",
+      2,
+      &Answer::Unknown,
+      &Ok(Answer::grid(vec!["#.", ".#"])),
+      &timings(),
+    );
+    grid.cached = true;
+    let failed = Record::failed(12, "", "", 1, "Could not read \"day12.txt\"");
+    assert_eq!(
+      to_csv(&[grid, failed]),
+      "8,\"Space, Images\",inputs/day08.txt,2,unknown,,\"#.\n.#\",,1500,20000,true\n\
+       12,,,1,error,,,\"Could not read \"\"day12.txt\"\"\",0,0,false\n"
+    );
+  }
+
+  #[test]
+  fn summary() {
+    let record =
+      |expected: Answer, actual| Record::new(1, "", "", 1, &expected, &actual, &timings());
+    let mut records = vec![
+      record(Answer::Integer(1), Ok(Answer::Integer(1))),
+      record(Answer::Unknown, Ok(Answer::Integer(1))),
+      record(Answer::Unknown, Err(SolveError::Unimplemented)),
+    ];
+    let summary = Summary::of(&records);
+    assert_eq!(
+      (summary.pass, summary.unknown, summary.unimplemented),
+      (1, 1, 1)
+    );
+    assert!(summary.is_success());
+
+    records.push(record(Answer::Integer(1), Ok(Answer::Integer(2))));
+    assert_eq!(Summary::of(&records).fail, 1);
+    assert!(!Summary::of(&records).is_success());
+
+    let failed = vec![Record::failed(3, "", "", 2, "missing input")];
+    assert!(!Summary::of(&failed).is_success());
+  }
+}
@@ -0,0 +1,97 @@
+//! A small thread pool for solving days concurrently while still reporting
+//! the results in order.
+
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+/// The number of threads to use for `jobs`, where 0 means one per CPU.
+pub fn threads(jobs: usize) -> usize {
+  match jobs {
+    0 => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+    jobs => jobs,
+  }
+}
+
+/// Runs `work` on each item using up to `jobs` threads (see `threads`), and
+/// calls `emit` with each index and result in item order, as soon as every
+/// earlier item is done. With one thread, everything runs on the caller's
+/// thread.
+pub fn run_ordered<T, R, F, E>(jobs: usize, items: &[T], work: F, mut emit: E)
+where
+  T: Sync,
+  R: Send,
+  F: Fn(&T) -> R + Sync,
+  E: FnMut(usize, R),
+{
+  let threads = threads(jobs).min(items.len());
+  if threads <= 1 {
+    for (i, item) in items.iter().enumerate() {
+      emit(i, work(item));
+    }
+    return;
+  }
+
+  let next = AtomicUsize::new(0);
+  let (sender, receiver) = mpsc::channel();
+  thread::scope(|scope| {
+    for _ in 0..threads {
+      let sender = sender.clone();
+      let (next, work) = (&next, &work);
+      scope.spawn(move || loop {
+        let i = next.fetch_add(1, Ordering::Relaxed);
+        match items.get(i) {
+          Some(item) if sender.send((i, work(item))).is_ok() => (),
+          _ => break,
+        }
+      });
+    }
+    drop(sender);
+
+    let mut pending = BTreeMap::new();
+    let mut next_emit = 0;
+    for (i, result) in receiver {
+      pending.insert(i, result);
+      while let Some(result) = pending.remove(&next_emit) {
+        emit(next_emit, result);
+        next_emit += 1;
+      }
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use std::thread;
+  use std::time::Duration;
+
+  use super::{run_ordered, threads};
+
+  #[test]
+  fn emits_in_order() {
+    let items: Vec<u64> = (0..20).collect();
+    for &jobs in &[1, 4, 0] {
+      let mut emitted = Vec::new();
+      run_ordered(
+        jobs,
+        &items,
+        |&n| {
+          // Later items finish first
+          thread::sleep(Duration::from_millis(20 - n));
+          n * n
+        },
+        |i, result| emitted.push((i, result)),
+      );
+      let expected: Vec<(usize, u64)> = items.iter().map(|&n| (n as usize, n * n)).collect();
+      assert_eq!(emitted, expected, "with {} jobs", jobs);
+    }
+  }
+
+  #[test]
+  fn thread_count() {
+    assert_eq!(threads(3), 3);
+    assert!(threads(0) >= 1);
+  }
+}
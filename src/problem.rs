@@ -1,28 +1,183 @@
+use std::convert::{Infallible, TryFrom};
+use std::fmt;
+use std::str::FromStr;
+
+pub use crate::error::SolveError;
+use crate::rng::Rng;
+
+/// The answer to one part of a puzzle.
+#[derive(Clone, Debug)]
+pub enum Answer {
+  Integer(i64),
+  Text(String),
+  /// A rendered image, one string per row.
+  Grid(Vec<String>),
+  /// No answer is known. Never equal to anything, including itself.
+  Unknown,
+}
+
+impl Answer {
+  pub fn grid<I, S>(rows: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    Answer::Grid(rows.into_iter().map(Into::into).collect())
+  }
+
+  pub fn is_known(&self) -> bool {
+    !matches!(self, Answer::Unknown)
+  }
+}
+
+impl PartialEq for Answer {
+  /// Integers compare numerically, also against text that holds a number,
+  /// and grids compare row by row, also against multi-line text.
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Answer::Integer(a), Answer::Integer(b)) => a == b,
+      (Answer::Integer(n), Answer::Text(text)) | (Answer::Text(text), Answer::Integer(n)) => {
+        text.trim().parse::<i64>() == Ok(*n)
+      }
+      (Answer::Text(a), Answer::Text(b)) => a == b,
+      (Answer::Grid(a), Answer::Grid(b)) => a == b,
+      (Answer::Grid(rows), Answer::Text(text)) | (Answer::Text(text), Answer::Grid(rows)) => {
+        rows.iter().map(String::as_str).eq(text.lines())
+      }
+      _ => false,
+    }
+  }
+}
+
+impl fmt::Display for Answer {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Answer::Integer(n) => write!(f, "{}", n),
+      Answer::Text(text) => write!(f, "{}", text),
+      Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+      Answer::Unknown => write!(f, "unknown"),
+    }
+  }
+}
+
+impl FromStr for Answer {
+  type Err = Infallible;
+
+  /// Numbers become integers and multi-line text becomes a grid.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if let Ok(n) = s.trim().parse() {
+      Ok(Answer::Integer(n))
+    } else if s.contains('\n') {
+      Ok(Answer::grid(s.lines()))
+    } else {
+      Ok(Answer::Text(s.to_string()))
+    }
+  }
+}
+
+impl From<i64> for Answer {
+  fn from(n: i64) -> Self {
+    Answer::Integer(n)
+  }
+}
+
+impl From<i32> for Answer {
+  fn from(n: i32) -> Self {
+    Answer::Integer(n.into())
+  }
+}
+
+impl From<u32> for Answer {
+  fn from(n: u32) -> Self {
+    Answer::Integer(n.into())
+  }
+}
+
+impl From<usize> for Answer {
+  fn from(n: usize) -> Self {
+    i64::try_from(n).map_or_else(|_| Answer::Text(n.to_string()), Answer::Integer)
+  }
+}
+
+impl From<&str> for Answer {
+  fn from(text: &str) -> Self {
+    Answer::Text(text.to_string())
+  }
+}
+
+impl From<String> for Answer {
+  fn from(text: String) -> Self {
+    Answer::Text(text)
+  }
+}
+
 pub trait Problem {
   fn new() -> Self
   where
     Self: Sized;
 
-  fn debug() -> Self
-  where
-    Self: Sized,
-  {
-    Self::new()
+  fn part_one(&self, _input: &str) -> Result<Answer, SolveError> {
+    Err(SolveError::Unimplemented)
   }
 
-  fn soln_one(&self) -> Option<String> {
-    None
+  fn part_two(&self, _input: &str) -> Result<Answer, SolveError> {
+    Err(SolveError::Unimplemented)
   }
 
-  fn part_one(&self, _input: &str) -> Option<String> {
-    None
+  /// A slow but straightforward solver for part one, that `verify` checks
+  /// `part_one` against.
+  fn reference_part_one(&self, _input: &str) -> Result<Answer, SolveError> {
+    Err(SolveError::Unimplemented)
   }
 
-  fn soln_two(&self) -> Option<String> {
-    None
+  /// A slow but straightforward solver for part two, that `verify` checks
+  /// `part_two` against.
+  fn reference_part_two(&self, _input: &str) -> Result<Answer, SolveError> {
+    Err(SolveError::Unimplemented)
   }
 
-  fn part_two(&self, _input: &str) -> Option<String> {
+  /// A random valid input, which grows with `size`. `None` if the day can
+  /// not generate inputs.
+  fn generate_input(&self, _rng: &mut Rng, _size: usize) -> Option<String> {
     None
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::Answer;
+
+  #[test]
+  fn integers_compare_numerically() {
+    assert_eq!(Answer::Integer(42), Answer::Text("042".to_string()));
+    assert_eq!(Answer::Text(" 42\n".to_string()), Answer::Integer(42));
+    assert_ne!(Answer::Integer(42), Answer::Text("forty-two".to_string()));
+    assert_ne!(Answer::Integer(42), Answer::Integer(43));
+  }
+
+  #[test]
+  fn grids_compare_by_row() {
+    let grid = Answer::grid(vec!["#.#", ".#."]);
+    assert_eq!(grid, Answer::grid(vec!["#.#", ".#."]));
+    assert_eq!(grid, Answer::Text("#.#\n.#.".to_string()));
+    assert_ne!(grid, Answer::grid(vec!["#.#"]));
+    assert_eq!(grid.to_string(), "#.#\n.#.");
+  }
+
+  #[test]
+  fn unknown_is_never_equal() {
+    assert_ne!(Answer::Unknown, Answer::Unknown);
+    assert_ne!(Answer::Unknown, Answer::Text("unknown".to_string()));
+  }
+
+  #[test]
+  fn parse_expected() {
+    assert!(matches!("1686".parse(), Ok(Answer::Integer(1686))));
+    assert!(matches!("-7".parse(), Ok(Answer::Integer(-7))));
+    assert!(matches!("ABCD".parse(), Ok(Answer::Text(_))));
+    assert_eq!(
+      "#.\n.#".parse::<Answer>().unwrap(),
+      Answer::grid(vec!["#.", ".#"])
+    );
+  }
+}
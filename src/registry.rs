@@ -0,0 +1,119 @@
+//! Days register themselves with `register_day!`; `build.rs` collects them
+//! into `DAYS`, so adding a day only needs a new `src/dayNN.rs`.
+
+use crate::problem::Problem;
+
+/// A registered day and how to construct its solver.
+pub struct Day {
+  pub number: usize,
+  pub title: &'static str,
+  /// A hash of the day's source and the library code it can use, set by
+  /// `build.rs`. It changes whenever the day's answers might.
+  pub version: &'static str,
+  create: fn() -> Box<dyn Problem>,
+}
+
+impl Day {
+  #[doc(hidden)]
+  pub const fn new(number: usize, title: &'static str, create: fn() -> Box<dyn Problem>) -> Self {
+    Self {
+      number,
+      title,
+      version: "",
+      create,
+    }
+  }
+
+  #[doc(hidden)]
+  pub const fn with_version(self, version: &'static str) -> Self {
+    Self { version, ..self }
+  }
+
+  pub fn create(&self) -> Box<dyn Problem> {
+    (self.create)()
+  }
+}
+
+#[doc(hidden)]
+pub fn create<P: Problem + 'static>() -> Box<dyn Problem> {
+  Box::new(P::new())
+}
+
+/// Registers a day's solver as `DAY` in the calling module.
+///
+/// ```ignore
+/// register_day!(DayOne, 1, "The Tyranny of the Rocket Equation");
+/// ```
+#[macro_export]
+macro_rules! register_day {
+  ($problem:ty, $number:expr, $title:expr) => {
+    pub const DAY: $crate::registry::Day =
+      $crate::registry::Day::new($number, $title, $crate::registry::create::<$problem>);
+  };
+}
+
+/// All registered days, in order.
+pub fn all() -> &'static [Day] {
+  crate::DAYS
+}
+
+pub fn get(number: usize) -> Option<&'static Day> {
+  all().iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{all, get};
+  use crate::problem::SolveError;
+  use crate::rng::Rng;
+
+  #[test]
+  fn days_are_in_order() {
+    // Days can be skipped, so only the order is fixed
+    let numbers: Vec<usize> = all().iter().map(|day| day.number).collect();
+    assert!(
+      numbers.windows(2).all(|pair| pair[0] < pair[1]),
+      "{:?}",
+      numbers
+    );
+    assert!(numbers.iter().all(|number| (1..=25).contains(number)));
+  }
+
+  #[test]
+  fn get_day() {
+    assert_eq!(get(3).unwrap().title, "Crossed Wires");
+    assert_eq!(get(3).unwrap().version.len(), 16);
+    assert_ne!(get(3).unwrap().version, get(4).unwrap().version);
+    assert!(get(0).is_none());
+    assert!(get(26).is_none());
+  }
+
+  #[test]
+  fn generated_inputs_parse() {
+    let generators: Vec<usize> = all()
+      .iter()
+      .filter(|day| day.create().generate_input(&mut Rng::new(0), 1).is_some())
+      .map(|day| day.number)
+      .collect();
+    assert_eq!(generators, vec![1, 3, 4, 6, 8, 10]);
+
+    for &number in &generators {
+      let problem = get(number).unwrap().create();
+      for size in &[1, 5, 20] {
+        let generate = || problem.generate_input(&mut Rng::for_input(7, number, *size), *size);
+        let input = generate().unwrap();
+        assert_eq!(Some(&input), generate().as_ref());
+        // Small inputs may be invalid for a part, but never malformed
+        for answer in &[problem.part_one(&input), problem.part_two(&input)] {
+          assert!(
+            !matches!(answer, Err(SolveError::Parse { .. })),
+            "day {} size {}: {:?}",
+            number,
+            size,
+            answer
+          );
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,284 @@
+//! Markdown and HTML reports of a run, to share as static files. Answers
+//! that are pictures (grids of pixels) are drawn: as block characters in
+//! Markdown, and as inline SVG in HTML.
+
+use std::time::Duration;
+
+use crate::output::{Record, Status, Summary};
+use crate::timing;
+
+const TITLE: &str = "Advent of Code 2019";
+
+/// The size of a pixel in the SVG drawings.
+const PIXEL: usize = 8;
+
+pub fn markdown(records: &[Record]) -> String {
+  let mut out = format!("# {}\n\n{}\n\n", TITLE, counts(records));
+  out.push_str("| Day | Title | Input | Part 1 | Part 2 | Time |\n");
+  out.push_str("| --- | --- | --- | --- | --- | --- |\n");
+  for row in rows(records) {
+    let cells: Vec<String> = row.cells().iter().map(|cell| md_escape(cell)).collect();
+    out.push_str(&format!("| {} |\n", cells.join(" | ")));
+  }
+
+  for (record, picture) in pictures(records) {
+    out.push_str(&format!("\n## {}\n\n```\n", picture_title(record)));
+    for line in picture.lines() {
+      let blocks: String = line
+        .chars()
+        .map(|c| if lit(c) { '█' } else { ' ' })
+        .collect();
+      out.push_str(blocks.trim_end());
+      out.push('\n');
+    }
+    out.push_str("```\n");
+  }
+  out
+}
+
+pub fn html(records: &[Record]) -> String {
+  let mut out = format!(
+    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n\
+     <style>{1}</style>\n</head>\n<body>\n<h1>{0}</h1>\n<p>{2}</p>\n",
+    TITLE,
+    STYLE,
+    html_escape(&counts(records))
+  );
+  out.push_str("<table>\n<tr><th>Day</th><th>Title</th><th>Input</th>");
+  out.push_str("<th>Part 1</th><th>Part 2</th><th>Time</th></tr>\n");
+  for row in rows(records) {
+    let cells = row.cells();
+    out.push_str("<tr>");
+    for (i, cell) in cells.iter().enumerate() {
+      let part = match i {
+        3 | 4 => row.parts[i - 3],
+        _ => None,
+      };
+      let class = part.map_or(String::new(), |record| {
+        format!(" class=\"{}\"", record.status.as_str())
+      });
+      out.push_str(&format!("<td{}>{}</td>", class, html_escape(cell)));
+    }
+    out.push_str("</tr>\n");
+  }
+  out.push_str("</table>\n");
+
+  for (record, picture) in pictures(records) {
+    out.push_str(&format!(
+      "<h2>{}</h2>\n{}\n",
+      html_escape(&picture_title(record)),
+      svg(picture)
+    ));
+  }
+  out.push_str("</body>\n</html>\n");
+  out
+}
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+table{border-collapse:collapse}\
+th,td{border:1px solid #ccc;padding:.3em .6em;text-align:left}\
+td.pass{background:#dfd}td.fail,td.error{background:#fdd}\
+td.unknown,td.unimplemented{background:#ffd}\
+svg{background:#222}rect{fill:#fc3}";
+
+/// One table row: a day and input, with its parts.
+struct Row<'a> {
+  first: &'a Record,
+  parts: [Option<&'a Record>; 2],
+  time: Duration,
+}
+
+impl Row<'_> {
+  fn cells(&self) -> Vec<String> {
+    let part = |record: Option<&Record>| record.map_or("-".to_string(), cell);
+    vec![
+      format!("{:02}", self.first.day),
+      self.first.title.clone(),
+      self.first.input.clone(),
+      part(self.parts[0]),
+      part(self.parts[1]),
+      timing::format(self.time),
+    ]
+  }
+}
+
+fn rows(records: &[Record]) -> Vec<Row<'_>> {
+  records
+    .chunk_by(|a, b| a.day == b.day && a.input == b.input)
+    .map(|records| Row {
+      first: &records[0],
+      parts: [1, 2].map(|part| records.iter().find(|r| r.part == part)),
+      time: Duration::from_nanos(records.iter().map(|r| r.parse_ns + r.solve_ns).sum()),
+    })
+    .collect()
+}
+
+/// A part's status and answer, on one line.
+fn cell(record: &Record) -> String {
+  let mark = match record.status {
+    Status::Pass => "✓",
+    Status::Fail | Status::Error => "✗",
+    Status::Unknown | Status::Unimplemented => "??",
+  };
+  let answer = match (&record.actual, &record.error) {
+    (Some(answer), _) if answer.contains('\n') => "picture below".to_string(),
+    (Some(answer), _) => answer.clone(),
+    (None, _) if record.status == Status::Unimplemented => "unimplemented".to_string(),
+    (None, error) => error.clone().unwrap_or_default(),
+  };
+  match (&record.status, &record.expected) {
+    (Status::Fail, Some(expected)) if !expected.contains('\n') => {
+      format!("{} {} (expected {})", mark, answer, expected)
+    }
+    _ => format!("{} {}", mark, answer),
+  }
+}
+
+fn counts(records: &[Record]) -> String {
+  let summary = Summary::of(records);
+  format!(
+    "{} passed, {} failed, {} errors, {} unknown, {} unimplemented",
+    summary.pass, summary.fail, summary.error, summary.unknown, summary.unimplemented
+  )
+}
+
+/// The answers that are pictures.
+fn pictures(records: &[Record]) -> impl Iterator<Item = (&Record, &str)> {
+  records.iter().filter_map(|record| {
+    record
+      .actual
+      .as_deref()
+      .filter(|answer| answer.contains('\n'))
+      .map(|answer| (record, answer))
+  })
+}
+
+fn picture_title(record: &Record) -> String {
+  format!(
+    "Day {:02}: {}, part {} ({})",
+    record.day, record.title, record.part, record.input
+  )
+}
+
+/// Whether a pixel is lit. Days draw unlit pixels as spaces or dots.
+fn lit(c: char) -> bool {
+  !matches!(c, ' ' | '.')
+}
+
+fn svg(picture: &str) -> String {
+  let rows: Vec<&str> = picture.lines().collect();
+  let width = rows
+    .iter()
+    .map(|row| row.chars().count())
+    .max()
+    .unwrap_or(0);
+  let mut out = format!(
+    "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+    width * PIXEL,
+    rows.len() * PIXEL
+  );
+  for (y, row) in rows.iter().enumerate() {
+    for (x, c) in row.chars().enumerate() {
+      if lit(c) {
+        out.push_str(&format!(
+          "<rect x=\"{}\" y=\"{}\" width=\"{p}\" height=\"{p}\"/>",
+          x * PIXEL,
+          y * PIXEL,
+          p = PIXEL
+        ));
+      }
+    }
+  }
+  out.push_str("</svg>");
+  out
+}
+
+fn md_escape(text: &str) -> String {
+  text.replace('|', "\\|")
+}
+
+fn html_escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::{html, markdown};
+  use crate::output::Record;
+  use crate::problem::{Answer, SolveError};
+  use crate::timing::Timings;
+
+  fn records() -> Vec<Record> {
+    let timings = Timings {
+      parse: Duration::from_micros(500),
+      solve: Duration::from_micros(1500),
+    };
+    vec![
+      Record::new(
+        8,
+        "Space <Image> Format",
+        "inputs/day08.txt",
+        1,
+        &Answer::Integer(1560),
+        &Ok(Answer::Integer(1561)),
+        &timings,
+      ),
+      Record::new(
+        8,
+        "Space <Image> Format",
+        "inputs/day08.txt",
+        2,
+        &Answer::Unknown,
+        &Ok(Answer::grid(vec!["#..#", ".##."])),
+        &timings,
+      ),
+      Record::new(
+        9,
+        "Sensor | Boost",
+        "inputs/day09.txt",
+        1,
+        &Answer::Unknown,
+        &Err(SolveError::Unimplemented),
+        &timings,
+      ),
+      Record::failed(10, "", "", 1, "Could not read \"day10.txt\""),
+    ]
+  }
+
+  #[test]
+  fn markdown_report() {
+    let report = markdown(&records());
+    assert!(report.starts_with("# Advent of Code 2019\n\n0 passed, 1 failed, 1 errors"));
+    assert!(report.contains(
+      "| 08 | Space <Image> Format | inputs/day08.txt | ✗ 1561 (expected 1560) \
+       | ?? picture below | 4.00ms |\n"
+    ));
+    assert!(report.contains("| 09 | Sensor \\| Boost | inputs/day09.txt | ?? unimplemented | - |"));
+    assert!(report.contains("| ✗ Could not read \"day10.txt\" |"));
+    assert!(report.ends_with(
+      "## Day 08: Space <Image> Format, part 2 (inputs/day08.txt)\n\n```\n█  █\n ██\n```\n"
+    ));
+  }
+
+  #[test]
+  fn html_report() {
+    let report = html(&records());
+    assert!(report.starts_with("<!DOCTYPE html>"));
+    assert!(report.contains("<td>Space &lt;Image&gt; Format</td>"));
+    assert!(report.contains("<td class=\"fail\">✗ 1561 (expected 1560)</td>"));
+    assert!(report.contains("<td class=\"error\">✗ Could not read &quot;day10.txt&quot;</td>"));
+    // Two lit pixels on each row
+    assert!(
+      report.contains("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"32\" height=\"16\">")
+    );
+    assert_eq!(report.matches("<rect ").count(), 4);
+    assert!(report.contains("<rect x=\"24\" y=\"0\" width=\"8\" height=\"8\"/>"));
+    assert!(!report.contains("<link") && !report.contains("<script"));
+  }
+}
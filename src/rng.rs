@@ -0,0 +1,101 @@
+//! A small seeded random number generator (xorshift64*), for generating
+//! puzzle inputs. The same seed always gives the same numbers.
+
+use std::ops::Range;
+
+pub struct Rng {
+  state: u64,
+}
+
+impl Rng {
+  pub fn new(seed: u64) -> Self {
+    // Scramble the seed (splitmix64), so nearby seeds give unrelated numbers
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    // The state must never be zero
+    Self { state: z.max(1) }
+  }
+
+  /// The generator for the `index`th generated input of `day`, so each input
+  /// can be reproduced from the seed alone.
+  pub fn for_input(seed: u64, day: usize, index: usize) -> Self {
+    Self::new(seed ^ ((day as u64) << 48) ^ index as u64)
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    self.state ^= self.state >> 12;
+    self.state ^= self.state << 25;
+    self.state ^= self.state >> 27;
+    self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+  }
+
+  /// A number in `range`, which must not be empty.
+  pub fn range(&mut self, range: Range<u64>) -> u64 {
+    assert!(!range.is_empty(), "empty range {:?}", range);
+    let width = range.end - range.start;
+    range.start + ((u128::from(self.next_u64()) * u128::from(width)) >> 64) as u64
+  }
+
+  /// A number below `n`, which must not be zero.
+  pub fn below(&mut self, n: usize) -> usize {
+    self.range(0..n as u64) as usize
+  }
+
+  /// True with probability `p`.
+  pub fn chance(&mut self, p: f64) -> bool {
+    ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+  }
+
+  pub fn shuffle<T>(&mut self, items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+      items.swap(i, self.below(i + 1));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Rng;
+
+  #[test]
+  fn seeded() {
+    let numbers = |seed| {
+      let mut rng = Rng::new(seed);
+      (0..8).map(|_| rng.next_u64()).collect::<Vec<_>>()
+    };
+    assert_eq!(numbers(7), numbers(7));
+    assert_ne!(numbers(7), numbers(8));
+    assert!(numbers(0).iter().all(|&n| n != 0));
+
+    let first = |seed, day, index| Rng::for_input(seed, day, index).next_u64();
+    assert_eq!(first(5, 4, 2), first(5, 4, 2));
+    assert_ne!(first(5, 4, 2), first(5, 4, 3));
+    assert_ne!(first(5, 4, 2), first(5, 10, 2));
+    assert_ne!(first(5, 4, 2), first(6, 4, 2));
+  }
+
+  #[test]
+  fn ranges() {
+    let mut rng = Rng::new(1);
+    let mut seen = [false; 5];
+    for _ in 0..1000 {
+      let n = rng.range(10..15);
+      assert!((10..15).contains(&n));
+      seen[n as usize - 10] = true;
+    }
+    assert!(seen.iter().all(|&seen| seen));
+    assert_eq!(rng.below(1), 0);
+
+    let hits = (0..10_000).filter(|_| rng.chance(0.25)).count();
+    assert!((2000..3000).contains(&hits), "{} hits", hits);
+    assert!(!rng.chance(0.));
+
+    let mut items: Vec<usize> = (0..20).collect();
+    rng.shuffle(&mut items);
+    assert_ne!(items, (0..20).collect::<Vec<_>>());
+    items.sort_unstable();
+    assert_eq!(items, (0..20).collect::<Vec<_>>());
+  }
+}
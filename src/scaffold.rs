@@ -0,0 +1,165 @@
+//! Generating the files for a new day.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::input;
+
+const NUMBERS: [&str; 25] = [
+  "One",
+  "Two",
+  "Three",
+  "Four",
+  "Five",
+  "Six",
+  "Seven",
+  "Eight",
+  "Nine",
+  "Ten",
+  "Eleven",
+  "Twelve",
+  "Thirteen",
+  "Fourteen",
+  "Fifteen",
+  "Sixteen",
+  "Seventeen",
+  "Eighteen",
+  "Nineteen",
+  "Twenty",
+  "TwentyOne",
+  "TwentyTwo",
+  "TwentyThree",
+  "TwentyFour",
+  "TwentyFive",
+];
+
+/// The name of the day's struct, e.g. `DayTwelve`.
+pub fn struct_name(day: usize) -> String {
+  assert!(
+    (1..=25).contains(&day),
+    "Day {} is not between 1 and 25",
+    day
+  );
+  format!("Day{}", NUMBERS[day - 1])
+}
+
+/// The source of a new day module that solves nothing yet.
+pub fn source(day: usize, title: &str) -> String {
+  TEMPLATE
+    .replace("{name}", &struct_name(day))
+    .replace("{number}", &day.to_string())
+    .replace("{title}", &format!("{:?}", title))
+    .replace("{day}", &format!("day{:02}", day))
+}
+
+const TEMPLATE: &str = r#"use crate::input;
+use crate::problem::{Answer, Problem, SolveError};
+use crate::timing;
+
+register_day!({name}, {number}, {title});
+
+#[derive(Default)]
+pub struct {name} {}
+
+impl {name} {
+  fn parse_input(input: &str) -> Result<Vec<&str>, SolveError> {
+    Ok(input::lines(input).map(|(_, line)| line).collect())
+  }
+}
+
+impl Problem for {name} {
+  fn new() -> Self {
+    Self {}
+  }
+
+  fn part_one(&self, input: &str) -> Result<Answer, SolveError> {
+    let lines = timing::parse(|| Self::parse_input(input))?;
+    // Shown with `--log {day}=debug`
+    debug!("{} lines", lines.len());
+    Err(SolveError::Unimplemented)
+  }
+
+  fn part_two(&self, input: &str) -> Result<Answer, SolveError> {
+    let _lines = timing::parse(|| Self::parse_input(input))?;
+    Err(SolveError::Unimplemented)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  // Puzzle examples go in tests/examples/{day}/, tests for the helpers go
+  // here.
+}
+"#;
+
+/// Creates `src/dayNN.rs` and an empty `inputs/dayNN.txt` under `root`,
+/// returning their paths. Nothing is written if either already exists.
+/// The day is registered by `build.rs` once the source exists.
+pub fn create(root: &Path, day: usize, title: &str) -> io::Result<Vec<PathBuf>> {
+  let source_path = root.join("src").join(format!("day{:02}.rs", day));
+  let input_path = root
+    .join(input::DEFAULT_DIR)
+    .join(format!("day{:02}.txt", day));
+  let files = vec![
+    (source_path, source(day, title)),
+    (input_path, String::new()),
+  ];
+
+  if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+    return Err(io::Error::new(
+      io::ErrorKind::AlreadyExists,
+      format!("{} already exists", path.display()),
+    ));
+  }
+  for (path, contents) in &files {
+    if let Some(dir) = path.parent() {
+      fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    file.write_all(contents.as_bytes())?;
+  }
+  Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+  use std::fs;
+
+  use super::{create, source, struct_name};
+
+  #[test]
+  fn names() {
+    assert_eq!(struct_name(1), "DayOne");
+    assert_eq!(struct_name(12), "DayTwelve");
+    assert_eq!(struct_name(25), "DayTwentyFive");
+  }
+
+  #[test]
+  fn template() {
+    let source = source(12, "The \"N-Body\" Problem");
+    assert!(source.contains("register_day!(DayTwelve, 12, \"The \\\"N-Body\\\" Problem\");"));
+    assert!(source.contains("impl Problem for DayTwelve {"));
+    assert!(source.contains("tests/examples/day12/"));
+    for placeholder in &["{name}", "{number}", "{title}", "{day}"] {
+      assert!(!source.contains(placeholder));
+    }
+  }
+
+  #[test]
+  fn refuses_to_overwrite() {
+    let root = env::temp_dir().join(format!("aoc2019-scaffold-{}", std::process::id()));
+    let created = create(&root, 12, "The N-Body Problem").unwrap();
+    assert_eq!(created.len(), 2);
+    assert_eq!(fs::read_to_string(&created[1]).unwrap(), "");
+
+    fs::write(&created[0], "// my solution").unwrap();
+    fs::remove_file(&created[1]).unwrap();
+    assert!(create(&root, 12, "The N-Body Problem").is_err());
+    assert_eq!(fs::read_to_string(&created[0]).unwrap(), "// my solution");
+    assert!(!created[1].exists());
+
+    fs::remove_dir_all(&root).unwrap();
+  }
+}
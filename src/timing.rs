@@ -0,0 +1,86 @@
+//! Splits the time a part takes into parsing and solving.
+//!
+//! Days wrap their input parsing in [`parse`]; [`measure`] times a whole part
+//! and attributes everything outside those calls to solving.
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+thread_local! {
+  static PARSE: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Timings {
+  pub parse: Duration,
+  pub solve: Duration,
+}
+
+impl Timings {
+  pub fn total(&self) -> Duration {
+    self.parse + self.solve
+  }
+}
+
+/// Runs `f`, counting its time as parsing.
+pub fn parse<T, F: FnOnce() -> T>(f: F) -> T {
+  let start = Instant::now();
+  let result = f();
+  PARSE.with(|parse| parse.set(parse.get() + start.elapsed()));
+  result
+}
+
+/// Runs `f` and returns how long it spent parsing and solving.
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Timings) {
+  let outer = PARSE.with(|parse| parse.replace(Duration::ZERO));
+  let start = Instant::now();
+  let result = f();
+  let total = start.elapsed();
+  let parse = PARSE.with(|parse| parse.replace(outer));
+
+  let timings = Timings {
+    parse,
+    solve: total.saturating_sub(parse),
+  };
+  (result, timings)
+}
+
+/// Formats a duration with a sensible unit, e.g. `12.34ms`.
+pub fn format(duration: Duration) -> String {
+  format!("{:.2?}", duration)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::thread;
+  use std::time::Duration;
+
+  use super::{measure, parse};
+
+  #[test]
+  fn splits_parse_and_solve() {
+    let (answer, timings) = measure(|| {
+      let n: u64 = parse(|| {
+        thread::sleep(Duration::from_millis(20));
+        "42".parse().unwrap()
+      });
+      thread::sleep(Duration::from_millis(10));
+      n
+    });
+    assert_eq!(answer, 42);
+    assert!(timings.parse >= Duration::from_millis(20));
+    assert!(timings.solve >= Duration::from_millis(10));
+  }
+
+  #[test]
+  fn nested_measurements_are_separate() {
+    let (_, outer) = measure(|| {
+      parse(|| thread::sleep(Duration::from_millis(5)));
+      let (_, inner) = measure(|| parse(|| thread::sleep(Duration::from_millis(5))));
+      assert!(inner.parse >= Duration::from_millis(5));
+    });
+    assert!(outer.parse >= Duration::from_millis(5));
+    // The inner measurement counts as solving for the outer one
+    assert!(outer.solve >= Duration::from_millis(5));
+  }
+}
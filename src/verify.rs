@@ -0,0 +1,98 @@
+//! Checks days' solvers against their slower reference solvers (see
+//! `Problem::reference_part_one`), on the real inputs and generated ones.
+
+use crate::problem::{Answer, Problem, SolveError};
+
+/// Where generated inputs that the solvers disagree on are saved.
+pub const FAILED_DIR: &str = ".cache/verify";
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+  /// The day has no reference solver for the part.
+  NoReference,
+  Agree,
+  /// Both solvers reject the input with the same error, so it says nothing
+  /// about whether they agree.
+  Skipped(SolveError),
+  Disagree {
+    actual: Result<Answer, SolveError>,
+    reference: Result<Answer, SolveError>,
+  },
+}
+
+/// Solves `part` of `input` with both solvers and compares the answers.
+/// Rejecting the input with different errors counts as disagreeing.
+pub fn compare(problem: &dyn Problem, part: usize, input: &str) -> Outcome {
+  let reference = match part {
+    1 => problem.reference_part_one(input),
+    _ => problem.reference_part_two(input),
+  };
+  if reference == Err(SolveError::Unimplemented) {
+    return Outcome::NoReference;
+  }
+  let actual = match part {
+    1 => problem.part_one(input),
+    _ => problem.part_two(input),
+  };
+  match (&actual, &reference) {
+    (Ok(a), Ok(b)) if a == b => Outcome::Agree,
+    (Err(a), Err(b)) if a == b => Outcome::Skipped(a.clone()),
+    _ => Outcome::Disagree { actual, reference },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{compare, Outcome};
+  use crate::problem::{Answer, Problem, SolveError};
+
+  /// Doubles a number, with a reference that is wrong for 3 and none for
+  /// part two.
+  struct Double;
+
+  impl Problem for Double {
+    fn new() -> Self {
+      Double
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, SolveError> {
+      let n: i64 = input
+        .parse()
+        .map_err(|_| SolveError::invalid("not a number"))?;
+      Ok(Answer::Integer(n * 2))
+    }
+
+    fn reference_part_one(&self, input: &str) -> Result<Answer, SolveError> {
+      match input.parse::<i64>() {
+        Ok(3) => Ok(Answer::Integer(7)),
+        Ok(n) => Ok(Answer::Integer(n + n)),
+        Err(_) if input.is_empty() => Err(SolveError::invalid("empty input")),
+        Err(_) => Err(SolveError::invalid("not a number")),
+      }
+    }
+  }
+
+  #[test]
+  fn compares_solvers() {
+    assert_eq!(compare(&Double, 1, "4"), Outcome::Agree);
+    assert_eq!(
+      compare(&Double, 1, "x"),
+      Outcome::Skipped(SolveError::invalid("not a number"))
+    );
+    assert_eq!(
+      compare(&Double, 1, ""),
+      Outcome::Disagree {
+        actual: Err(SolveError::invalid("not a number")),
+        reference: Err(SolveError::invalid("empty input")),
+      }
+    );
+    assert_eq!(
+      compare(&Double, 1, "3"),
+      Outcome::Disagree {
+        actual: Ok(Answer::Integer(6)),
+        reference: Ok(Answer::Integer(7)),
+      }
+    );
+    assert_eq!(compare(&Double, 2, "4"), Outcome::NoReference);
+  }
+}
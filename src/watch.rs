@@ -0,0 +1,161 @@
+//! Watching a day's source and inputs for changes, and describing how its
+//! results changed between runs.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::input;
+use crate::output::{Record, Status};
+use crate::timing;
+
+pub const SOURCE_DIR: &str = "src";
+
+/// The modification time of each existing file.
+pub type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+pub fn snapshot(paths: &[PathBuf]) -> Snapshot {
+  paths
+    .iter()
+    .filter_map(|path| {
+      let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+      Some((path.clone(), modified))
+    })
+    .collect()
+}
+
+/// The files that only affect `day`: its source and its inputs, or `input`
+/// if one was given instead.
+pub fn day_files(day: usize, input: Option<&Path>) -> Vec<PathBuf> {
+  let mut files = vec![Path::new(SOURCE_DIR).join(format!("day{:02}.rs", day))];
+  match input {
+    Some(input) => files.push(input.to_path_buf()),
+    None => {
+      // The input directory may not exist yet
+      files.extend(input::find_inputs(input::DEFAULT_DIR, day).unwrap_or_default());
+    }
+  }
+  files
+}
+
+/// The sources shared by every day, e.g. the Intcode computer.
+pub fn shared_files() -> io::Result<Vec<PathBuf>> {
+  let mut files = vec![PathBuf::from("Cargo.toml"), PathBuf::from("build.rs")];
+  find_sources(Path::new(SOURCE_DIR), &mut files)?;
+  files.retain(|path| !is_day_source(path));
+  Ok(files)
+}
+
+fn find_sources(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+  for entry in fs::read_dir(dir)? {
+    let path = entry?.path();
+    if path.is_dir() {
+      find_sources(&path, files)?;
+    } else if path.extension().is_some_and(|ext| ext == "rs") {
+      files.push(path);
+    }
+  }
+  Ok(())
+}
+
+fn is_day_source(path: &Path) -> bool {
+  path.parent() == Some(Path::new(SOURCE_DIR))
+    && path
+      .file_stem()
+      .and_then(|stem| stem.to_str())
+      .and_then(|stem| stem.strip_prefix("day"))
+      .is_some_and(|num| num.len() == 2 && num.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Describes how a part's result changed since the previous run, if there
+/// was one, e.g. `Part 1: 42 -> 43 (pass -> fail), 1.20ms -> 980.00µs (-18.3%)`.
+pub fn describe(before: Option<&Record>, after: &Record) -> String {
+  let time = |record: &Record| Duration::from_nanos(record.parse_ns + record.solve_ns);
+  let before = match before {
+    Some(before) => before,
+    None => {
+      return format!(
+        "Part {}: {} ({}) in {}",
+        after.part,
+        result(after),
+        after.status.as_str(),
+        timing::format(time(after))
+      );
+    }
+  };
+
+  let answer = match (result(before), result(after)) {
+    (old, new) if old == new => new,
+    (old, new) => format!("{} -> {}", old, new),
+  };
+  let status = match (before.status, after.status) {
+    (old, new) if old == new => new.as_str().to_string(),
+    (old, new) => format!("{} -> {}", old.as_str(), new.as_str()),
+  };
+  let change = time(after).as_secs_f64() / time(before).as_secs_f64() - 1.;
+  format!(
+    "Part {}: {} ({}), {} -> {} ({:+.1}%)",
+    after.part,
+    answer,
+    status,
+    timing::format(time(before)),
+    timing::format(time(after)),
+    change * 100.
+  )
+}
+
+/// The answer or error, on one line.
+fn result(record: &Record) -> String {
+  match (&record.actual, &record.error) {
+    (Some(answer), _) if answer.contains('\n') => format!("{} rows", answer.lines().count()),
+    (Some(answer), _) => answer.clone(),
+    (None, _) if record.status == Status::Unimplemented => "unimplemented".to_string(),
+    (None, error) => error.clone().unwrap_or_default(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+  use std::time::Duration;
+
+  use super::{describe, is_day_source};
+  use crate::output::Record;
+  use crate::problem::Answer;
+  use crate::timing::Timings;
+
+  fn record(answer: Answer, expected: i64, solve_us: u64) -> Record {
+    let timings = Timings {
+      parse: Duration::ZERO,
+      solve: Duration::from_micros(solve_us),
+    };
+    let expected = Answer::Integer(expected);
+    Record::new(5, "", "", 1, &expected, &Ok(answer), &timings)
+  }
+
+  #[test]
+  fn describe_changes() {
+    let before = record(Answer::Integer(42), 42, 1000);
+    assert_eq!(describe(None, &before), "Part 1: 42 (pass) in 1.00ms");
+    assert_eq!(
+      describe(Some(&before), &record(Answer::Integer(42), 42, 800)),
+      "Part 1: 42 (pass), 1.00ms -> 800.00µs (-20.0%)"
+    );
+    assert_eq!(
+      describe(Some(&before), &record(Answer::Integer(43), 42, 1500)),
+      "Part 1: 42 -> 43 (pass -> fail), 1.00ms -> 1.50ms (+50.0%)"
+    );
+    let grid = record(Answer::grid(vec!["#.", ".#"]), 42, 1000);
+    assert!(describe(Some(&before), &grid).starts_with("Part 1: 42 -> 2 rows"));
+  }
+
+  #[test]
+  fn day_sources() {
+    assert!(is_day_source(Path::new("src/day07.rs")));
+    assert!(!is_day_source(Path::new("src/intcode/mod.rs")));
+    assert!(!is_day_source(Path::new("src/main.rs")));
+    assert!(!is_day_source(Path::new("src/day7.rs")));
+  }
+}
@@ -0,0 +1,254 @@
+//! Checks each day's public API, which other crates build on, against the
+//! puzzle examples. The signatures are pinned too, so changing one is a
+//! deliberate decision rather than a side effect of refactoring a day.
+
+use std::collections::{HashMap, HashSet};
+
+use num_bigint::BigInt;
+
+use aoc2019::day01::DayOne;
+use aoc2019::day02::DayTwo;
+use aoc2019::day03::{Coord, DayThree, Direction, Intersection, Movement, WireMaps};
+use aoc2019::day04::DayFour;
+use aoc2019::day05::DayFive;
+use aoc2019::day06::DaySix;
+use aoc2019::day07::DaySeven;
+use aoc2019::day08::{self, DayEight, Image};
+use aoc2019::day09::DayNine;
+use aoc2019::day10::{Asteroid, DayTen, NumVisible};
+use aoc2019::day11::{self, Color, DayEleven, Position, Robot};
+use aoc2019::problem::SolveError;
+
+#[test]
+// Spelling the types out in full is the point here
+#[allow(clippy::type_complexity)]
+fn signatures() {
+  let _: fn(u32) -> u32 = DayOne::fuel;
+  let _: fn(u32) -> u32 = DayOne::total_fuel;
+  let _: fn(&[i64], i64, i64) -> Result<i64, SolveError> = DayTwo::run;
+  let _: fn(&str) -> Result<(Vec<Movement>, Vec<Movement>), SolveError> = DayThree::parse_wires;
+  let _: fn(&[Movement]) -> WireMaps = DayThree::build_maps;
+  let _: fn(&WireMaps, &[Movement]) -> HashSet<Intersection> = DayThree::get_intersections;
+  let _: fn(&[Movement], &[Movement]) -> HashSet<Intersection> = DayThree::intersections;
+  let _: fn(&str) -> Result<(u32, u32), SolveError> = DayFour::parse_range;
+  let _: fn(u32, u32, fn(&[u32; 10]) -> bool) -> usize = DayFour::count_passwords;
+  let _: fn(&[i64], Vec<i64>) -> Result<i64, SolveError> = DayFive::diagnose;
+  let _: fn(&str) -> Result<HashMap<&str, &str>, SolveError> = DaySix::parse_orbit_map;
+  let _: for<'a> fn(&HashMap<&'a str, &'a str>) -> Result<HashMap<&'a str, usize>, SolveError> =
+    DaySix::get_height_map;
+  let _: fn(&HashMap<&str, &str>, &str, &str) -> Result<usize, SolveError> = DaySix::transfers;
+  let _: fn(&[u8], &[i64]) -> Result<i64, SolveError> = DaySeven::series_output;
+  let _: fn(&[u8], &[i64]) -> Result<i64, SolveError> = DaySeven::feedback_output;
+  let _: fn(&str) -> Result<Image, SolveError> = DayEight::parse_image;
+  let _: fn(&[BigInt], i64) -> Result<BigInt, SolveError> = DayNine::boost;
+  let _: fn(&str) -> Result<Vec<Asteroid>, SolveError> = DayTen::parse_asteroids;
+  let _: fn(&[Asteroid]) -> Result<(Asteroid, NumVisible), SolveError> = DayTen::best_position;
+  let _: fn(&[Asteroid], Asteroid, usize) -> Option<Asteroid> = DayTen::vaporized;
+  let _: fn(Vec<i64>) -> Robot = Robot::new;
+  let _: fn(&mut Robot, &mut HashMap<Position, Color>) -> Result<(), SolveError> = Robot::run;
+  let _: fn(Vec<i64>, Color) -> Result<HashMap<Position, Color>, SolveError> = DayEleven::paint;
+  let _: fn(&HashMap<Position, Color>) -> Vec<String> = DayEleven::render;
+}
+
+#[test]
+fn day01_fuel() {
+  assert_eq!(DayOne::fuel(12), 2);
+  assert_eq!(DayOne::fuel(1969), 654);
+  assert_eq!(DayOne::fuel(100_756), 33_583);
+  assert_eq!(DayOne::fuel(2), 0);
+  assert_eq!(DayOne::total_fuel(14), 2);
+  assert_eq!(DayOne::total_fuel(1969), 966);
+  assert_eq!(DayOne::total_fuel(100_756), 50_346);
+}
+
+#[test]
+fn day02_run() {
+  assert_eq!(DayTwo::run(&[1, 0, 0, 0, 99], 0, 0), Ok(2));
+  assert_eq!(DayTwo::run(&[1, 0, 0, 0, 99], 4, 4), Ok(198));
+  assert!(DayTwo::run(&[99], 0, 0).is_err());
+}
+
+#[test]
+fn day03_wires() {
+  let (wire1, wire2) = DayThree::parse_wires("R8,U5,L5,D3\nU7,R6,D4,L4").unwrap();
+  assert_eq!(
+    wire1[0],
+    Movement {
+      dir: Direction::Right,
+      amount: 8
+    }
+  );
+
+  let maps = DayThree::build_maps(&wire1);
+  let intersections = DayThree::get_intersections(&maps, &wire2);
+  assert_eq!(intersections, DayThree::intersections(&wire1, &wire2));
+  let mut points: Vec<Coord> = intersections.iter().map(|i| i.point).collect();
+  points.sort_by_key(|point| (point.x, point.y));
+  let expected = [(0, 0), (3, 3), (6, 5)].iter();
+  assert_eq!(
+    points,
+    expected
+      .map(|&(x, y)| Coord::from_vals(x, y))
+      .collect::<Vec<_>>()
+  );
+  assert!(intersections.contains(&Intersection {
+    wire1_steps: 20,
+    wire2_steps: 20,
+    point: Coord::from_vals(3, 3),
+  }));
+  assert_eq!(Coord::from_vals(3, -3).manhattan(), 6);
+}
+
+#[test]
+fn day04_passwords() {
+  assert_eq!(
+    DayFour::parse_range("111111-111122\n"),
+    Ok((111_111, 111_122))
+  );
+  assert_eq!(
+    DayFour::count_passwords(111_111, 111_122, DayFour::has_pair),
+    10
+  );
+  assert_eq!(
+    DayFour::count_passwords(111_111, 111_122, DayFour::has_exact_pair),
+    1
+  );
+  assert_eq!(DayFour::count_passwords(100, 110, |_| true), 0);
+}
+
+#[test]
+fn day05_diagnose() {
+  assert_eq!(DayFive::diagnose(&[3, 0, 4, 0, 99], vec![7]), Ok(7));
+  let equal_to_8 = [3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
+  assert_eq!(DayFive::diagnose(&equal_to_8, vec![8]), Ok(1));
+  assert_eq!(DayFive::diagnose(&equal_to_8, vec![5]), Ok(0));
+}
+
+#[test]
+fn day06_orbits() {
+  let input = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L\nK)YOU\nI)SAN";
+  let orbit_map = DaySix::parse_orbit_map(input).unwrap();
+  assert_eq!(orbit_map["B"], "COM");
+
+  let heights = DaySix::get_height_map(&orbit_map).unwrap();
+  assert_eq!(heights["COM"], 0);
+  assert_eq!(heights["L"], 7);
+  assert_eq!(heights.values().sum::<usize>(), 42 + 7 + 5);
+  assert_eq!(DaySix::transfers(&orbit_map, "YOU", "SAN"), Ok(4));
+  assert!(DaySix::transfers(&orbit_map, "YOU", "COM").is_err());
+
+  let broken = DaySix::parse_orbit_map("COM)B\nX)Y").unwrap();
+  assert!(DaySix::get_height_map(&broken).is_err());
+}
+
+#[test]
+fn day07_amplifiers() {
+  let series = [
+    3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0,
+  ];
+  assert_eq!(
+    DaySeven::series_output(&[4, 3, 2, 1, 0], &series),
+    Ok(43210)
+  );
+
+  let feedback = [
+    3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28, -1, 28, 1005,
+    28, 6, 99, 0, 0, 5,
+  ];
+  assert_eq!(
+    DaySeven::feedback_output(&[9, 8, 7, 6, 5], &feedback),
+    Ok(139_629_729)
+  );
+}
+
+#[test]
+fn day08_image() {
+  let image = Image::new(2, 2, vec![0, 2, 2, 2, 1, 1, 2, 2, 2, 2, 1, 2, 0, 0, 0, 0]);
+  assert_eq!(image.num_layers(), 4);
+  assert_eq!(image.iter().next(), Some(&[0, 2, 2, 2][..]));
+  let pixels: Vec<u8> = (0..2)
+    .flat_map(|y| (0..2).map(move |x| (x, y)))
+    .map(|(x, y)| image.get_pixel(x, y))
+    .collect();
+  assert_eq!(pixels, vec![0, 1, 1, 0]);
+
+  let input = "1".repeat(day08::WIDTH * day08::HEIGHT);
+  let image = DayEight::parse_image(&input).unwrap();
+  assert_eq!(image.get_layer(0).len(), day08::WIDTH * day08::HEIGHT);
+  assert!(DayEight::parse_image("0120").is_err());
+}
+
+#[test]
+fn day09_boost() {
+  let program: Vec<BigInt> = [104, 1_125_899_906_842_624_i64, 99]
+    .iter()
+    .map(|&word| BigInt::from(word))
+    .collect();
+  assert_eq!(
+    DayNine::boost(&program, 1),
+    Ok(BigInt::from(1_125_899_906_842_624_i64))
+  );
+}
+
+#[test]
+fn day10_asteroids() {
+  let asteroids = DayTen::parse_asteroids(".#..#\n.....\n#####\n....#\n...##").unwrap();
+  assert_eq!(asteroids[0], Asteroid(1, 0));
+  assert_eq!(asteroids[2], Asteroid(0, -2));
+  assert_eq!(DayTen::best_position(&asteroids), Ok((Asteroid(3, -4), 8)));
+  assert!(DayTen::best_position(&[]).is_err());
+
+  let field = "\
+.#....#####...#..
+##...##.#####..##
+##...#...#.#####.
+..#.....#...###..
+..#.#.....#....##";
+  let asteroids = DayTen::parse_asteroids(field).unwrap();
+  let station = Asteroid(8, -3);
+  let order: Vec<Asteroid> = (1..=9)
+    .map(|n| DayTen::vaporized(&asteroids, station, n).unwrap())
+    .collect();
+  let expected = [
+    (8, 1),
+    (9, 0),
+    (9, 1),
+    (10, 0),
+    (9, 2),
+    (11, 1),
+    (12, 1),
+    (11, 2),
+    (15, 1),
+  ];
+  assert_eq!(
+    order,
+    expected
+      .iter()
+      .map(|&(x, y)| Asteroid(x, -y))
+      .collect::<Vec<_>>()
+  );
+  assert_eq!(DayTen::vaporized(&asteroids, station, 0), None);
+  assert_eq!(
+    DayTen::vaporized(&asteroids, station, asteroids.len()),
+    None
+  );
+}
+
+#[test]
+fn day11_robot() {
+  // Paints each panel the color it was, and turns right then left
+  let program = vec![3, 100, 4, 100, 104, 1, 3, 100, 4, 100, 104, 0, 99];
+
+  let mut robot = Robot::new(program.clone());
+  let mut painted = HashMap::new();
+  robot.run(&mut painted).unwrap();
+  assert_eq!(robot.position(), Position { x: 1, y: 1 });
+  assert_eq!(painted.len(), 2);
+  assert_eq!(painted[&Position { x: 0, y: 0 }], day11::DEFAULT_COLOR);
+
+  let painted = DayEleven::paint(program, Color::White).unwrap();
+  assert_eq!(painted[&Position { x: 0, y: 0 }], Color::White);
+  assert_eq!(painted[&Position { x: 1, y: 0 }], Color::Black);
+  assert_eq!(DayEleven::render(&painted), vec!["#."]);
+  assert!(DayEleven::render(&HashMap::new()).is_empty());
+}
@@ -0,0 +1,56 @@
+//! Runs every example in `tests/examples` against its day.
+
+use std::fs;
+use std::path::Path;
+
+use aoc2019::{examples, registry};
+
+#[test]
+fn examples() {
+  let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(examples::DEFAULT_DIR);
+  let found = examples::find(&dir).unwrap();
+  assert!(!found.is_empty(), "No examples in {}", dir.display());
+
+  let mut failures = Vec::new();
+  for (day, path) in found {
+    let name = path.strip_prefix(&dir).unwrap().display().to_string();
+    let example = match fs::read_to_string(&path)
+      .map_err(|e| e.to_string())
+      .and_then(|text| examples::parse(&text))
+    {
+      Ok(example) => example,
+      Err(e) => {
+        failures.push(format!("{}: {}", name, e));
+        continue;
+      }
+    };
+    let problem = match registry::get(day) {
+      Some(day) => day.create(),
+      None => {
+        failures.push(format!("{}: day {} is not registered", name, day));
+        continue;
+      }
+    };
+
+    for part in 1..=2 {
+      let expected = match example.expected(part) {
+        Some(expected) => expected,
+        None => continue,
+      };
+      let actual = match part {
+        1 => problem.part_one(&example.input),
+        _ => problem.part_two(&example.input),
+      };
+      match actual {
+        Ok(ref actual) if actual == expected => {}
+        Ok(actual) => failures.push(format!(
+          "{} part {}: expected {}, got {}",
+          name, part, expected, actual
+        )),
+        Err(e) => failures.push(format!("{} part {}: {}", name, part, e)),
+      }
+    }
+  }
+
+  assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}